@@ -1,7 +1,10 @@
 use mint::Point2;
 use rand::prelude::*;
 use std::{thread, time::Duration};
-use tiler::{App, Context, Frame, KeyCode, TileSet};
+use tiler::{
+    App, Backend, ColorMode, Config, Context, Frame, KeyCode, Modifiers, NinePatch, Rect, TileSet,
+    UpdateMode, WindowSize,
+};
 
 #[derive(Debug, Copy, Clone, TileSet)]
 pub enum Tiles {
@@ -42,8 +45,9 @@ impl State {
 impl App for State {
     const NAME: &'static str = "rogue";
     const SIZE: Point2<usize> = Point2 { x: 80, y: 30 };
+    const UPDATE_MODE: UpdateMode = UpdateMode::WaitForInput;
 
-    fn update(&mut self, frame: &mut Frame) {
+    fn update(&mut self, _ctx: Context, frame: &mut Frame) {
         let room = Room::new(0, 0, 5, 7);
         room.draw(frame);
         let room = Room::new(20, 15, 28, 18);
@@ -51,7 +55,7 @@ impl App for State {
         frame[(self.player.x, self.player.y)] = Tiles::Character.to_char();
     }
 
-    fn key_down_event(&mut self, mut ctx: Context, keycode: KeyCode) {
+    fn key_down_event(&mut self, mut ctx: Context, keycode: KeyCode, _modifiers: Modifiers) {
         match keycode {
             KeyCode::Left => {
                 if self.player.x > 0 {
@@ -107,62 +111,31 @@ impl NinePatch for Room {
     const BOTTOM_RIGHT: Self::TileSet = Tiles::WallSE;
 }
 
-pub trait NinePatch {
-    type TileSet: TileSet;
-    const TOP_LEFT: Self::TileSet;
-    const TOP: Self::TileSet;
-    const TOP_RIGHT: Self::TileSet;
-    const LEFT: Self::TileSet;
-    const MIDDLE: Self::TileSet;
-    const RIGHT: Self::TileSet;
-    const BOTTOM_LEFT: Self::TileSet;
-    const BOTTOM: Self::TileSet;
-    const BOTTOM_RIGHT: Self::TileSet;
-
-    fn draw(frame: &mut Frame, rect: Rect) {
-        let Rect {
-            top_left,
-            bottom_right,
-        } = rect;
-
-        for x in top_left.x + 1..bottom_right.x - 1 {
-            frame[(x, top_left.y)] = Self::TOP.to_char();
-            frame[(x, bottom_right.y - 1)] = Self::BOTTOM.to_char();
-        }
-        for y in top_left.y + 1..bottom_right.y - 1 {
-            frame[(top_left.x, y)] = Self::LEFT.to_char();
-            frame[(bottom_right.x - 1, y)] = Self::RIGHT.to_char();
-        }
-        frame[(top_left.x, top_left.y)] = Self::TOP_LEFT.to_char();
-        frame[(bottom_right.x - 1, top_left.y)] = Self::TOP_RIGHT.to_char();
-        frame[(top_left.x, bottom_right.y - 1)] = Self::BOTTOM_LEFT.to_char();
-        frame[(bottom_right.x - 1, bottom_right.y - 1)] = Self::BOTTOM_RIGHT.to_char();
-        for x in (top_left.x + 1)..(bottom_right.x - 1) {
-            for y in (top_left.y + 1)..(bottom_right.y - 1) {
-                frame[(x, y)] = Self::MIDDLE.to_char();
-            }
+pub fn main() {
+    let backend = if std::env::args().any(|arg| arg == "--terminal") {
+        Backend::Terminal(ColorMode::detect())
+    } else {
+        Backend::Wgpu
+    };
+    let config = Config::default()
+        .backend(backend)
+        .size(WindowSize::Cells(Point2 { x: 80, y: 30 }))
+        .x11_class("floating", "floating");
+    let result = match tiler::run(State::new(), config.clone()) {
+        Err(e) if e.is_graphics_unavailable() => {
+            eprintln!("{}; falling back to the terminal", e);
+            tiler::run(
+                State::new(),
+                config.backend(Backend::Terminal(ColorMode::detect())),
+            )
         }
-    }
-}
-
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub struct Rect {
-    top_left: Point2<usize>,
-    bottom_right: Point2<usize>,
-}
-
-impl Rect {
-    fn from_parts(left: usize, top: usize, right: usize, bottom: usize) -> Self {
-        Self {
-            top_left: Point2 { x: left, y: top },
-            bottom_right: Point2 {
-                x: right,
-                y: bottom,
-            },
+        result => result,
+    };
+    match result {
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
         }
     }
 }
-
-pub fn main() {
-    tiler::run(State::new());
-}
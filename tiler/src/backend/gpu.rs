@@ -0,0 +1,368 @@
+use crate::{
+    backend::GridLayout, text::WIDE_CHAR_TAIL, App, AppContainer, Color, Config, Error, Frame,
+    KeyCode, Rasterizer, FONT, FULL_BLOCK,
+};
+use mint::Point2;
+use raw_window_handle::HasRawWindowHandle;
+use std::{
+    cell::Cell,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+use wgpu_glyph::{GlyphBrushBuilder, Scale, Section};
+use winit::{
+    dpi::PhysicalSize,
+    event::{ElementState, Event, KeyboardInput, MouseScrollDelta, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    platform::{desktop::EventLoopExtDesktop, unix::WindowBuilderExtUnix},
+    window::{Icon, WindowBuilder},
+};
+
+/// Pressing this key saves the current frame to a PNG file in the working directory.
+const SCREENSHOT_KEY: KeyCode = KeyCode::F12;
+
+/// Save `frame` as `screenshot-<unix time>.png`, using cells of the same size as the window's.
+fn save_screenshot(frame: &Frame, cell_size: Point2<u32>) {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let path = format!("screenshot-{}.png", secs);
+    match Rasterizer::with_cell_size(cell_size)
+        .render(frame)
+        .save_png(&path)
+    {
+        Ok(()) => log::info!("saved screenshot to {}", path),
+        Err(e) => log::error!("could not save screenshot to {}: {}", path, e),
+    }
+}
+
+thread_local! {
+    /// Set while `catch_wgpu` is running on this thread, to keep the panic hook quiet.
+    static CATCHING: Cell<bool> = Cell::new(false);
+}
+
+/// Run `f`, turning a panic into an error.
+///
+/// wgpu reports most failures (no adapter, lost device, ...) by panicking, so this is the only
+/// way to recover from them. The panic message isn't printed since it ends up in the error.
+///
+/// The first call wraps whatever panic hook is installed, so panics on other threads (or outside
+/// `catch_wgpu`) still go to it as before.
+fn catch_wgpu<T>(make_err: fn(String) -> Error, f: impl FnOnce() -> T) -> Result<T, Error> {
+    static WRAP_HOOK: Once = Once::new();
+    WRAP_HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.with(Cell::get) {
+                hook(info);
+            }
+        }));
+    });
+    CATCHING.with(|catching| catching.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|catching| catching.set(false));
+    result.map_err(|payload| Error::from_panic(make_err, payload))
+}
+
+pub(crate) fn run<A>(app: A, config: Config) -> Result<i32, Error>
+where
+    A: App,
+{
+    let mut app_ctr = AppContainer::new(app);
+    app_ctr.state.fullscreen = config.fullscreen;
+    let instance = wgpu::Instance::new();
+    let adapter = catch_wgpu(Error::NoAdapter, || {
+        instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+        })
+    })?;
+    let mut device = catch_wgpu(Error::Device, || {
+        adapter.request_device(&wgpu::DeviceDescriptor {
+            extensions: wgpu::Extensions {
+                anisotropic_filtering: false,
+            },
+            limits: wgpu::Limits { max_bind_groups: 1 },
+        })
+    })?;
+    let mut event_loop = EventLoop::new();
+    let monitor = event_loop.primary_monitor();
+    let mut builder = WindowBuilder::new()
+        .with_title(config.title_for::<A>())
+        .with_resizable(config.resizable);
+    if let Some(size) = config.pixel_size::<A>() {
+        let size = PhysicalSize::new(size.x as f64, size.y as f64);
+        builder = builder.with_inner_size(size.to_logical(monitor.hidpi_factor()));
+    }
+    if config.fullscreen {
+        builder = builder.with_fullscreen(Some(monitor));
+    }
+    if let Some(icon) = &config.icon {
+        builder = builder.with_window_icon(Some(Icon::from_rgba(
+            icon.data().to_vec(),
+            icon.width(),
+            icon.height(),
+        )?));
+    }
+    if let Some((name, class)) = &config.x11_class {
+        builder = builder.with_class(name.clone(), class.clone());
+    }
+    let window = builder.build(&event_loop)?;
+    let mut fullscreen = config.fullscreen;
+    let surface = catch_wgpu(Error::Surface, || {
+        instance.create_surface(window.raw_window_handle())
+    })?;
+
+    let render_format = wgpu::TextureFormat::Bgra8UnormSrgb;
+    let present_mode = if config.vsync {
+        wgpu::PresentMode::Vsync
+    } else {
+        wgpu::PresentMode::NoVsync
+    };
+    let [r, g, b, a] = config.clear_color.to_f32();
+    let clear_color = wgpu::Color {
+        r: r as f64,
+        g: g as f64,
+        b: b as f64,
+        a: a as f64,
+    };
+    let mut size = window.inner_size().to_physical(window.hidpi_factor());
+
+    let mut swap_chain = catch_wgpu(Error::Surface, || {
+        device.create_swap_chain(
+            &surface,
+            &wgpu::SwapChainDescriptor {
+                usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+                format: render_format,
+                width: size.width.round() as u32,
+                height: size.height.round() as u32,
+                present_mode,
+            },
+        )
+    })?;
+
+    let mut glyph_brush =
+        GlyphBrushBuilder::using_font_bytes(FONT).build(&mut device, render_format);
+    let window_size = |size: PhysicalSize| Point2 {
+        x: size.width as f32,
+        y: size.height as f32,
+    };
+    let mut layout = GridLayout::new(A::GRID_MODE, window_size(size), A::SIZE);
+    // start first, so the app hears about its real grid size after `on_start` like any resize
+    app_ctr.start();
+    app_ctr.resize(layout.grid);
+
+    //let mut last_resize_time: Option<Instant> = None;
+    let mut tmp_str = String::from(" ");
+    // set if something goes wrong inside the event loop, so we can return it once it exits
+    let mut result = Ok(());
+    event_loop.run_return(|event, _, control_flow| {
+        match event {
+            Event::EventsCleared => {
+                // update state
+                let now = Instant::now();
+                if app_ctr.run_due_updates(now) {
+                    window.request_redraw();
+                }
+                *control_flow = match app_ctr.next_update(now) {
+                    Some(when) => ControlFlow::WaitUntil(when),
+                    None => ControlFlow::Wait,
+                };
+            }
+            Event::WindowEvent {
+                event: WindowEvent::RedrawRequested,
+                ..
+            } => {
+                log::trace!("draw");
+                // draw
+                let mut encoder =
+                    device.create_command_encoder(&wgpu::CommandEncoderDescriptor { todo: 0 });
+
+                let frame = swap_chain.get_next_texture();
+                {
+                    let _ = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                        color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                            attachment: &frame.view,
+                            resolve_target: None,
+                            load_op: wgpu::LoadOp::Clear,
+                            store_op: wgpu::StoreOp::Store,
+                            clear_color,
+                        }],
+                        depth_stencil_attachment: None,
+                    });
+                }
+                let mut draw_char = |pos: Point2<usize>, ch: char, color: Color| {
+                    tmp_str.clear();
+                    tmp_str.push(ch);
+                    let origin = layout.cell_origin(pos);
+                    glyph_brush.queue(Section {
+                        text: &tmp_str,
+                        screen_position: (origin.x, origin.y),
+                        color: color.to_f32(),
+                        scale: Scale {
+                            x: layout.cell.x * 2.0,
+                            y: layout.cell.y,
+                        },
+                        bounds: (size.width as f32, size.height as f32),
+                        ..Section::default()
+                    });
+                };
+                let frame_size = app_ctr.frame_buf.size();
+                for x in 0..frame_size.x {
+                    for y in 0..frame_size.y {
+                        let idx = Point2 { x, y };
+                        let ch = app_ctr.frame_buf.get(idx);
+                        // background
+                        if !ch.bg.is_transparent() {
+                            draw_char(idx, FULL_BLOCK, ch.bg);
+                        }
+                        // foreground
+                        if !ch.fg.is_transparent() && ch.ch != WIDE_CHAR_TAIL {
+                            draw_char(idx, ch.ch, ch.fg);
+                        }
+                    }
+                }
+                if let Err(e) = glyph_brush.draw_queued(
+                    &mut device,
+                    &mut encoder,
+                    &frame.view,
+                    size.width.round() as u32,
+                    size.height.round() as u32,
+                ) {
+                    result = Err(Error::Draw(e));
+                    *control_flow = ControlFlow::Exit;
+                    return;
+                }
+                device.get_queue().submit(&[encoder.finish()]);
+            }
+            Event::WindowEvent {
+                event: WindowEvent::CloseRequested,
+                ..
+            } => {
+                app_ctr.close_requested();
+                if !app_ctr.should_exit() {
+                    log::info!("closing the window was vetoed by the app");
+                }
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                virtual_keycode,
+                                state,
+                                modifiers,
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                if let Some(keycode) = virtual_keycode {
+                    match state {
+                        ElementState::Pressed if keycode == SCREENSHOT_KEY => {
+                            let cell_size = Point2 {
+                                x: layout.cell.x.round().max(1.0) as u32,
+                                y: layout.cell.y.round().max(1.0) as u32,
+                            };
+                            save_screenshot(&app_ctr.frame_buf, cell_size);
+                        }
+                        // the app never saw the press, so it shouldn't see the release either
+                        ElementState::Released if keycode == SCREENSHOT_KEY => (),
+                        ElementState::Pressed => app_ctr.key_down(keycode, Some(modifiers)),
+                        ElementState::Released => app_ctr.key_up(keycode, Some(modifiers)),
+                    }
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::ReceivedCharacter(ch),
+                ..
+            } => app_ctr.text_input(ch),
+            Event::WindowEvent {
+                event: WindowEvent::Focused(focused),
+                ..
+            } => app_ctr.focus_changed(focused),
+            Event::WindowEvent {
+                event: WindowEvent::CursorMoved { position, .. },
+                ..
+            } => {
+                let position = position.to_physical(window.hidpi_factor());
+                app_ctr.mouse_moved(layout.cell_at(Point2 {
+                    x: position.x as f32,
+                    y: position.y as f32,
+                }));
+            }
+            Event::WindowEvent {
+                event: WindowEvent::CursorLeft { .. },
+                ..
+            } => app_ctr.mouse_moved(None),
+            Event::WindowEvent {
+                event: WindowEvent::MouseInput { state, button, .. },
+                ..
+            } => match state {
+                ElementState::Pressed => app_ctr.mouse_down(button),
+                ElementState::Released => app_ctr.mouse_up(button),
+            },
+            Event::WindowEvent {
+                event: WindowEvent::MouseWheel { delta, .. },
+                ..
+            } => {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => Point2 { x, y },
+                    MouseScrollDelta::PixelDelta(pixels) => {
+                        let pixels = pixels.to_physical(window.hidpi_factor());
+                        Point2 {
+                            x: pixels.x as f32 / layout.cell.x,
+                            y: pixels.y as f32 / layout.cell.y,
+                        }
+                    }
+                };
+                app_ctr.mouse_wheel(delta);
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Resized(new_size),
+                ..
+            } => {
+                // let's debounce this every 100ms.
+                // TODO this isn't debouncing, need to schedule a resize
+                /*
+                if let Some(time) = last_resize_time {
+                    if Instant::now().duration_since(time) < Duration::from_millis(100) {
+                        return;
+                    }
+                }
+                last_resize_time = Some(Instant::now());
+                */
+                size = new_size.to_physical(window.hidpi_factor());
+                layout = GridLayout::new(A::GRID_MODE, window_size(size), A::SIZE);
+                app_ctr.resize(layout.grid);
+                swap_chain = device.create_swap_chain(
+                    &surface,
+                    &wgpu::SwapChainDescriptor {
+                        usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+                        format: render_format,
+                        width: size.width.round() as u32,
+                        height: size.height.round() as u32,
+                        present_mode,
+                    },
+                );
+                window.request_redraw();
+            }
+            _ => (),
+        }
+        if app_ctr.state.fullscreen != fullscreen {
+            fullscreen = app_ctr.state.fullscreen;
+            window.set_fullscreen(if fullscreen {
+                Some(window.current_monitor())
+            } else {
+                None
+            });
+        }
+        if app_ctr.should_exit() {
+            *control_flow = ControlFlow::Exit;
+        }
+    });
+    let code = app_ctr.finish();
+    result.map(|()| code)
+}
@@ -0,0 +1,156 @@
+use crate::{App, AppContainer, Error, Frame, Image, KeyCode, MouseButton, Rasterizer};
+use mint::Point2;
+use std::time::Duration;
+
+/// Drives an `App` without a window or GPU.
+///
+/// Input is supplied by calling the methods on this type, and the current frame can be inspected
+/// directly or rasterized to an `Image` using the bundled font.
+pub struct Headless<A>
+where
+    A: App,
+{
+    app_ctr: AppContainer<A>,
+    rasterizer: Rasterizer,
+}
+
+impl<A> Headless<A>
+where
+    A: App,
+{
+    /// Wrap the app, using the default rasterizer.
+    pub fn new(app: A) -> Self {
+        Self::with_rasterizer(app, Rasterizer::new())
+    }
+
+    /// Wrap the app, using the given rasterizer to render frames. This calls `App::on_start`.
+    pub fn with_rasterizer(app: A, rasterizer: Rasterizer) -> Self {
+        let mut app_ctr = AppContainer::new(app);
+        app_ctr.start();
+        Headless {
+            app_ctr,
+            rasterizer,
+        }
+    }
+
+    /// Clear the frame and call `App::update`, as if one tick had passed.
+    ///
+    /// Time is simulated, so the app sees the tick duration of its `UpdateMode` as the delta (or
+    /// 1/60th of a second if it waits for input).
+    pub fn update(&mut self) {
+        self.app_ctr.update(self.app_ctr.manual_tick());
+    }
+
+    /// Clear the frame and call `App::update`, as if `delta` had passed since the last update.
+    pub fn update_with(&mut self, delta: Duration) {
+        self.app_ctr.update(delta);
+    }
+
+    /// Change the size of the grid, as if the window had been resized with
+    /// `GridMode::FitWindow`.
+    pub fn resize(&mut self, size: Point2<usize>) {
+        self.app_ctr.resize(size);
+    }
+
+    /// Send a key press to the app.
+    ///
+    /// Modifiers are worked out from the modifier keys (shift, ctrl, ...) currently held down.
+    pub fn key_down(&mut self, keycode: KeyCode) {
+        self.app_ctr.key_down(keycode, None);
+    }
+
+    /// Send a key release to the app.
+    pub fn key_up(&mut self, keycode: KeyCode) {
+        self.app_ctr.key_up(keycode, None);
+    }
+
+    /// Send typed text to the app, one character at a time.
+    pub fn type_text(&mut self, text: &str) {
+        for ch in text.chars() {
+            self.app_ctr.text_input(ch);
+        }
+    }
+
+    /// Move the mouse over `cell`, or off the grid if it is `None`.
+    pub fn mouse_move(&mut self, cell: Option<Point2<usize>>) {
+        self.app_ctr.mouse_moved(cell);
+    }
+
+    /// Press a mouse button wherever the mouse currently is.
+    pub fn mouse_down(&mut self, button: MouseButton) {
+        self.app_ctr.mouse_down(button);
+    }
+
+    /// Release a mouse button wherever the mouse currently is.
+    pub fn mouse_up(&mut self, button: MouseButton) {
+        self.app_ctr.mouse_up(button);
+    }
+
+    /// Scroll the mouse wheel by `delta` lines.
+    pub fn mouse_wheel(&mut self, delta: Point2<f32>) {
+        self.app_ctr.mouse_wheel(delta);
+    }
+
+    /// Ask the app to close, as if the window's close button was pressed.
+    pub fn request_close(&mut self) {
+        self.app_ctr.close_requested();
+    }
+
+    /// Tell the app the window gained or lost focus.
+    pub fn set_focused(&mut self, focused: bool) {
+        self.app_ctr.focus_changed(focused);
+    }
+
+    /// Whether the app has asked to exit.
+    pub fn exited(&self) -> bool {
+        self.app_ctr.should_exit()
+    }
+
+    /// The code the app asked to exit with, if it has.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.app_ctr.state.exit_code
+    }
+
+    /// Stop the app, calling `App::on_exit` (only the first time), and return its exit code.
+    pub fn finish(&mut self) -> i32 {
+        self.app_ctr.finish()
+    }
+
+    /// The frame drawn by the last call to `update`.
+    pub fn frame(&self) -> &Frame {
+        &self.app_ctr.frame_buf
+    }
+
+    /// Rasterize the current frame.
+    pub fn render(&self) -> Image {
+        self.rasterizer.render(&self.app_ctr.frame_buf)
+    }
+
+    pub fn app(&self) -> &A {
+        &self.app_ctr.app
+    }
+
+    pub fn app_mut(&mut self) -> &mut A {
+        &mut self.app_ctr.app
+    }
+
+    pub fn into_app(self) -> A {
+        self.app_ctr.app
+    }
+}
+
+pub(crate) fn run<A>(app: A, ticks: u64) -> Result<i32, Error>
+where
+    A: App,
+{
+    let mut headless = Headless::new(app);
+    for _ in 0..ticks {
+        if headless.exited() {
+            break;
+        }
+        headless.update();
+        // make sure the frame can actually be drawn
+        headless.render();
+    }
+    Ok(headless.finish())
+}
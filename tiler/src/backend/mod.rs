@@ -0,0 +1,121 @@
+//! The different ways an `App` can be run.
+use mint::Point2;
+
+pub(crate) mod gpu;
+pub(crate) mod headless;
+pub(crate) mod term;
+
+/// Which backend drives the app. Set with `Config::backend`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Backend {
+    /// Open a window and render using wgpu. This is the default.
+    Wgpu,
+    /// Don't open a window, just update the app `ticks` times (or until it exits).
+    ///
+    /// Use `Headless` directly to send input and inspect the rendered frames.
+    Headless { ticks: u64 },
+    /// Draw to the terminal the program was started from, using ANSI escape codes.
+    Terminal(term::ColorMode),
+}
+
+/// How the grid of cells relates to the size of the window.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GridMode {
+    /// The grid is always `App::SIZE`. Cells are scaled to fit the window, keeping the aspect
+    /// ratio of the glyphs, and any space left over is filled with black bars.
+    Fixed,
+    /// The grid grows and shrinks to fill the window, with cells of `cell_size` pixels. `App::SIZE`
+    /// is only the size before the window first reports its size, and `App::resized` is called
+    /// whenever the grid changes.
+    ///
+    /// In the terminal the cell size is fixed by the terminal, so the grid simply matches its
+    /// size in characters.
+    FitWindow { cell_size: Point2<u32> },
+}
+
+/// The height of a cell divided by its width, which is what the font's glyphs are designed for.
+pub(crate) const CELL_ASPECT: f32 = 2.0;
+
+/// Where the grid of cells sits in a window, in physical pixels.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct GridLayout {
+    /// The top-left corner of the grid.
+    pub origin: Point2<f32>,
+    /// The size of a single cell.
+    pub cell: Point2<f32>,
+    /// The number of cells.
+    pub grid: Point2<usize>,
+}
+
+impl GridLayout {
+    /// Lay out the grid in a window according to `mode`, using `grid` cells in fixed mode.
+    pub fn new(mode: GridMode, window: Point2<f32>, grid: Point2<usize>) -> Self {
+        match mode {
+            GridMode::Fixed => Self::letterbox(window, grid),
+            GridMode::FitWindow { cell_size } => Self::fit(window, cell_size),
+        }
+    }
+
+    /// Scale the grid to fill as much of the window as possible without distorting the cells,
+    /// and center it.
+    pub fn letterbox(window: Point2<f32>, grid: Point2<usize>) -> Self {
+        let width = (window.x / grid.x.max(1) as f32)
+            .min(window.y / (grid.y.max(1) as f32 * CELL_ASPECT))
+            .max(0.0);
+        let cell = Point2 {
+            x: width,
+            y: width * CELL_ASPECT,
+        };
+        GridLayout {
+            origin: Self::centered(window, cell, grid),
+            cell,
+            grid,
+        }
+    }
+
+    /// Fit as many cells of `cell_size` into the window as possible (at least one), and center
+    /// them.
+    pub fn fit(window: Point2<f32>, cell_size: Point2<u32>) -> Self {
+        let cell = Point2 {
+            x: cell_size.x.max(1) as f32,
+            y: cell_size.y.max(1) as f32,
+        };
+        let grid = Point2 {
+            x: ((window.x / cell.x).floor() as usize).max(1),
+            y: ((window.y / cell.y).floor() as usize).max(1),
+        };
+        GridLayout {
+            origin: Self::centered(window, cell, grid),
+            cell,
+            grid,
+        }
+    }
+
+    fn centered(window: Point2<f32>, cell: Point2<f32>, grid: Point2<usize>) -> Point2<f32> {
+        Point2 {
+            x: ((window.x - grid.x as f32 * cell.x) / 2.0).max(0.0).floor(),
+            y: ((window.y - grid.y as f32 * cell.y) / 2.0).max(0.0).floor(),
+        }
+    }
+
+    /// The position of the top-left corner of the cell.
+    pub fn cell_origin(&self, Point2 { x, y }: Point2<usize>) -> Point2<f32> {
+        Point2 {
+            x: self.origin.x + x as f32 * self.cell.x,
+            y: self.origin.y + y as f32 * self.cell.y,
+        }
+    }
+
+    /// The cell under the pixel position, or `None` if it's outside the grid.
+    pub fn cell_at(&self, pos: Point2<f32>) -> Option<Point2<usize>> {
+        let x = ((pos.x - self.origin.x) / self.cell.x).floor();
+        let y = ((pos.y - self.origin.y) / self.cell.y).floor();
+        if x < 0.0 || y < 0.0 || x >= self.grid.x as f32 || y >= self.grid.y as f32 {
+            return None;
+        }
+        Some(Point2 {
+            x: x as usize,
+            y: y as usize,
+        })
+    }
+}
@@ -0,0 +1,654 @@
+use crate::{
+    text::WIDE_CHAR_TAIL, App, AppContainer, Char, Color, Config, Error, Frame, GridMode, KeyCode,
+    Modifiers, MouseButton,
+};
+use crossterm::{
+    event::{
+        self, Event, KeyCode as TermKeyCode, KeyEvent, KeyModifiers, MouseButton as TermButton,
+        MouseEvent,
+    },
+    terminal,
+};
+use mint::Point2;
+use std::{
+    env, f32,
+    fmt::Write as _,
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+use unicode_width::UnicodeWidthChar;
+
+/// How long to wait for input at a time when no update is due.
+const IDLE_WAIT: Duration = Duration::from_secs(60);
+
+/// The colors a terminal can display.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorMode {
+    /// 24-bit color.
+    TrueColor,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// The 16 basic ANSI colors.
+    Ansi16,
+}
+
+impl ColorMode {
+    /// Guess what the terminal supports from the `COLORTERM` and `TERM` environment variables.
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorMode::TrueColor;
+        }
+        let term = env::var("TERM").unwrap_or_default();
+        if term.contains("256color") {
+            ColorMode::Ansi256
+        } else {
+            ColorMode::Ansi16
+        }
+    }
+
+    /// Write the escape sequence to select `color` as the foreground (`fg == true`) or
+    /// background color.
+    fn write_sgr(self, out: &mut String, [r, g, b]: [u8; 3], fg: bool) {
+        match self {
+            ColorMode::TrueColor => {
+                let kind = if fg { 38 } else { 48 };
+                write!(out, "\x1b[{};2;{};{};{}m", kind, r, g, b).unwrap();
+            }
+            ColorMode::Ansi256 => {
+                let kind = if fg { 38 } else { 48 };
+                write!(out, "\x1b[{};5;{}m", kind, ansi256(r, g, b)).unwrap();
+            }
+            ColorMode::Ansi16 => {
+                let idx = ansi16(r, g, b);
+                let code = match (fg, idx < 8) {
+                    (true, true) => 30 + idx,
+                    (true, false) => 90 + idx - 8,
+                    (false, true) => 40 + idx,
+                    (false, false) => 100 + idx - 8,
+                };
+                write!(out, "\x1b[{}m", code).unwrap();
+            }
+        }
+    }
+}
+
+/// Nearest entry in the xterm 256-color palette (either the 6x6x6 cube or the gray ramp).
+fn ansi256(r: u8, g: u8, b: u8) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    fn cube_idx(v: u8) -> u8 {
+        if v < 48 {
+            0
+        } else if v < 115 {
+            1
+        } else {
+            (v - 35) / 40
+        }
+    }
+    let (ri, gi, bi) = (cube_idx(r), cube_idx(g), cube_idx(b));
+    let cube = [
+        LEVELS[ri as usize],
+        LEVELS[gi as usize],
+        LEVELS[bi as usize],
+    ];
+    let avg = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_idx = if avg > 238 { 23 } else { (avg.max(8) - 8) / 10 };
+    let gray = 8 + gray_idx as u8 * 10;
+    if dist([r, g, b], [gray, gray, gray]) < dist([r, g, b], cube) {
+        232 + gray_idx as u8
+    } else {
+        16 + 36 * ri + 6 * gi + bi
+    }
+}
+
+/// Nearest of the 16 basic colors, using the xterm default palette.
+fn ansi16(r: u8, g: u8, b: u8) -> u8 {
+    const PALETTE: [[u8; 3]; 16] = [
+        [0, 0, 0],
+        [205, 0, 0],
+        [0, 205, 0],
+        [205, 205, 0],
+        [0, 0, 238],
+        [205, 0, 205],
+        [0, 205, 205],
+        [229, 229, 229],
+        [127, 127, 127],
+        [255, 0, 0],
+        [0, 255, 0],
+        [255, 255, 0],
+        [92, 92, 255],
+        [255, 0, 255],
+        [0, 255, 255],
+        [255, 255, 255],
+    ];
+    (0..16)
+        .min_by_key(|&i| dist([r, g, b], PALETTE[i as usize]))
+        .unwrap()
+}
+
+fn dist(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(&a, &b)| (a as i32 - b as i32).pow(2) as u32)
+        .sum()
+}
+
+/// The colors a cell will actually be drawn with. Transparency is resolved against black (like
+/// the window's clear color), and `None` means the terminal's default background.
+fn resolve(ch: &Char) -> (Option<[u8; 3]>, Option<[u8; 3]>) {
+    let bg = if !ch.bg.is_transparent() {
+        Some(mix([0.0; 3], ch.bg))
+    } else {
+        None
+    };
+    let fg = if !ch.fg.is_transparent() {
+        let under = bg.map(to_f32).unwrap_or([0.0; 3]);
+        Some(mix(under, ch.fg))
+    } else {
+        None
+    };
+    (fg, bg)
+}
+
+fn mix(under: [f32; 3], over: Color) -> [u8; 3] {
+    let over = over.to_f32();
+    let mut out = [0; 3];
+    for i in 0..3 {
+        let v = under[i] + (over[i] - under[i]) * over[3];
+        out[i] = (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    }
+    out
+}
+
+fn to_f32(c: [u8; 3]) -> [f32; 3] {
+    [
+        c[0] as f32 / 255.0,
+        c[1] as f32 / 255.0,
+        c[2] as f32 / 255.0,
+    ]
+}
+
+/// Owns the terminal while the app is running, and puts it back the way it was when dropped.
+struct Terminal {
+    out: io::Stdout,
+    color_mode: ColorMode,
+    /// The last frame we drew, so we only have to redraw cells that changed.
+    prev: Option<Frame>,
+    /// The size of the terminal in cells.
+    size: Point2<usize>,
+    buf: String,
+}
+
+impl Terminal {
+    fn new(color_mode: ColorMode) -> io::Result<Self> {
+        let size = term_size()?;
+        terminal::enable_raw_mode().map_err(to_io)?;
+        let mut term = Terminal {
+            out: io::stdout(),
+            color_mode,
+            prev: None,
+            size,
+            buf: String::new(),
+        };
+        // alternate screen, hide cursor, clear, report mouse events
+        term.out.write_all(b"\x1b[?1049h\x1b[?25l\x1b[2J")?;
+        term.out
+            .write_all(b"\x1b[?1000h\x1b[?1002h\x1b[?1015h\x1b[?1006h")?;
+        term.out.flush()?;
+        Ok(term)
+    }
+
+    /// Set the title of the terminal window, if the terminal supports it.
+    fn set_title(&mut self, title: &str) -> io::Result<()> {
+        // control characters would end the escape sequence early
+        let title: String = title.chars().filter(|ch| !ch.is_control()).collect();
+        write!(self.out, "\x1b]2;{}\x07", title)?;
+        self.out.flush()
+    }
+
+    /// Forget what is on the screen, so the next draw repaints everything.
+    fn invalidate(&mut self) -> io::Result<()> {
+        self.size = term_size()?;
+        self.prev = None;
+        self.out.write_all(b"\x1b[0m\x1b[2J")
+    }
+
+    fn draw(&mut self, frame: &Frame) -> io::Result<()> {
+        let size = frame.size();
+        if self.prev.as_ref().map(|prev| prev.size()) != Some(size) {
+            self.prev = None;
+        }
+        self.buf.clear();
+        // the colors the terminal is currently set to, if we know them
+        let mut cur_colors = None;
+        // where the cursor will be after the last write
+        let mut cursor = None;
+        for y in 0..size.y.min(self.size.y) {
+            for x in 0..size.x.min(self.size.x) {
+                let pos = Point2 { x, y };
+                let ch = frame.get(pos);
+                // the tail of a wide character is drawn along with the character itself
+                if ch.ch == WIDE_CHAR_TAIL && x > 0 && is_wide(frame.get(Point2 { x: x - 1, y }).ch)
+                {
+                    continue;
+                }
+                let tail = Point2 { x: x + 1, y };
+                let has_tail = is_wide(ch.ch)
+                    && x + 1 < size.x.min(self.size.x)
+                    && frame.get(tail).ch == WIDE_CHAR_TAIL;
+                if let Some(prev) = &self.prev {
+                    if prev.get(pos) == ch && (!has_tail || prev.get(tail) == frame.get(tail)) {
+                        continue;
+                    }
+                }
+                if cursor != Some(pos) {
+                    write!(self.buf, "\x1b[{};{}H", y + 1, x + 1).unwrap();
+                }
+                let colors = resolve(ch);
+                if cur_colors != Some(colors) {
+                    let (fg, bg) = colors;
+                    self.buf.push_str("\x1b[0m");
+                    if let Some(fg) = fg {
+                        self.color_mode.write_sgr(&mut self.buf, fg, true);
+                    }
+                    if let Some(bg) = bg {
+                        self.color_mode.write_sgr(&mut self.buf, bg, false);
+                    }
+                    cur_colors = Some(colors);
+                }
+                // a wide character without room for its tail would spill into the next cell
+                let visible =
+                    colors.0.is_some() && !ch.ch.is_control() && (has_tail || !is_wide(ch.ch));
+                if visible {
+                    self.buf.push(ch.ch);
+                } else if has_tail {
+                    self.buf.push_str("  ");
+                } else {
+                    self.buf.push(' ');
+                }
+                let width = if has_tail { 2 } else { 1 };
+                cursor = Some(Point2 { x: x + width, y });
+            }
+        }
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        self.buf.push_str("\x1b[0m");
+        self.out.write_all(self.buf.as_bytes())?;
+        self.out.flush()?;
+        self.prev = Some(frame.clone());
+        Ok(())
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        // stop reporting mouse events, reset colors, show cursor, leave alternate screen
+        let _ = self
+            .out
+            .write_all(b"\x1b[?1006l\x1b[?1015l\x1b[?1002l\x1b[?1000l");
+        let _ = self.out.write_all(b"\x1b[0m\x1b[?25h\x1b[?1049l");
+        let _ = self.out.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn is_wide(ch: char) -> bool {
+    UnicodeWidthChar::width(ch) == Some(2)
+}
+
+fn term_size() -> io::Result<Point2<usize>> {
+    let (x, y) = terminal::size().map_err(to_io)?;
+    Ok(Point2 {
+        x: x as usize,
+        y: y as usize,
+    })
+}
+
+fn to_io(e: crossterm::ErrorKind) -> io::Error {
+    io::Error::other(e.to_string())
+}
+
+/// Map a terminal key to the equivalent `KeyCode`, if there is one.
+fn translate_key(code: TermKeyCode) -> Option<KeyCode> {
+    Some(match code {
+        TermKeyCode::Backspace => KeyCode::Back,
+        TermKeyCode::Enter => KeyCode::Return,
+        TermKeyCode::Left => KeyCode::Left,
+        TermKeyCode::Right => KeyCode::Right,
+        TermKeyCode::Up => KeyCode::Up,
+        TermKeyCode::Down => KeyCode::Down,
+        TermKeyCode::Home => KeyCode::Home,
+        TermKeyCode::End => KeyCode::End,
+        TermKeyCode::PageUp => KeyCode::PageUp,
+        TermKeyCode::PageDown => KeyCode::PageDown,
+        TermKeyCode::Tab | TermKeyCode::BackTab => KeyCode::Tab,
+        TermKeyCode::Delete => KeyCode::Delete,
+        TermKeyCode::Insert => KeyCode::Insert,
+        TermKeyCode::Esc => KeyCode::Escape,
+        TermKeyCode::F(n) => match n {
+            1 => KeyCode::F1,
+            2 => KeyCode::F2,
+            3 => KeyCode::F3,
+            4 => KeyCode::F4,
+            5 => KeyCode::F5,
+            6 => KeyCode::F6,
+            7 => KeyCode::F7,
+            8 => KeyCode::F8,
+            9 => KeyCode::F9,
+            10 => KeyCode::F10,
+            11 => KeyCode::F11,
+            12 => KeyCode::F12,
+            _ => return None,
+        },
+        TermKeyCode::Char(ch) => return translate_char(ch),
+        TermKeyCode::Null => return None,
+    })
+}
+
+fn translate_modifiers(code: TermKeyCode, modifiers: KeyModifiers) -> Modifiers {
+    // Shifted characters don't always come with the shift modifier set.
+    let shifted = match code {
+        TermKeyCode::Char(ch) => ch.is_uppercase(),
+        TermKeyCode::BackTab => true,
+        _ => false,
+    };
+    Modifiers {
+        shift: shifted || modifiers.contains(KeyModifiers::SHIFT),
+        ctrl: modifiers.contains(KeyModifiers::CONTROL),
+        alt: modifiers.contains(KeyModifiers::ALT),
+        logo: false,
+    }
+}
+
+fn translate_char(ch: char) -> Option<KeyCode> {
+    Some(match ch.to_ascii_lowercase() {
+        'a' => KeyCode::A,
+        'b' => KeyCode::B,
+        'c' => KeyCode::C,
+        'd' => KeyCode::D,
+        'e' => KeyCode::E,
+        'f' => KeyCode::F,
+        'g' => KeyCode::G,
+        'h' => KeyCode::H,
+        'i' => KeyCode::I,
+        'j' => KeyCode::J,
+        'k' => KeyCode::K,
+        'l' => KeyCode::L,
+        'm' => KeyCode::M,
+        'n' => KeyCode::N,
+        'o' => KeyCode::O,
+        'p' => KeyCode::P,
+        'q' => KeyCode::Q,
+        'r' => KeyCode::R,
+        's' => KeyCode::S,
+        't' => KeyCode::T,
+        'u' => KeyCode::U,
+        'v' => KeyCode::V,
+        'w' => KeyCode::W,
+        'x' => KeyCode::X,
+        'y' => KeyCode::Y,
+        'z' => KeyCode::Z,
+        '0' => KeyCode::Key0,
+        '1' => KeyCode::Key1,
+        '2' => KeyCode::Key2,
+        '3' => KeyCode::Key3,
+        '4' => KeyCode::Key4,
+        '5' => KeyCode::Key5,
+        '6' => KeyCode::Key6,
+        '7' => KeyCode::Key7,
+        '8' => KeyCode::Key8,
+        '9' => KeyCode::Key9,
+        ' ' => KeyCode::Space,
+        '\'' => KeyCode::Apostrophe,
+        '\\' => KeyCode::Backslash,
+        ',' => KeyCode::Comma,
+        '=' => KeyCode::Equals,
+        '`' => KeyCode::Grave,
+        '[' => KeyCode::LBracket,
+        ']' => KeyCode::RBracket,
+        '-' => KeyCode::Minus,
+        '.' => KeyCode::Period,
+        ';' => KeyCode::Semicolon,
+        '/' => KeyCode::Slash,
+        '+' => KeyCode::Add,
+        '*' => KeyCode::Multiply,
+        ':' => KeyCode::Colon,
+        '@' => KeyCode::At,
+        '^' => KeyCode::Caret,
+        '_' => KeyCode::Underline,
+        _ => return None,
+    })
+}
+
+fn translate_button(button: TermButton) -> MouseButton {
+    match button {
+        TermButton::Left => MouseButton::Left,
+        TermButton::Right => MouseButton::Right,
+        TermButton::Middle => MouseButton::Middle,
+    }
+}
+
+/// Pass a mouse event on to the app. Terminals report positions in cells already, but they can
+/// be outside the frame if the terminal is bigger than it.
+fn handle_mouse<A>(app_ctr: &mut AppContainer<A>, event: MouseEvent)
+where
+    A: App,
+{
+    let (column, row) = match event {
+        MouseEvent::Down(_, column, row, _)
+        | MouseEvent::Up(_, column, row, _)
+        | MouseEvent::Drag(_, column, row, _)
+        | MouseEvent::ScrollDown(column, row, _)
+        | MouseEvent::ScrollUp(column, row, _) => (column as usize, row as usize),
+    };
+    let size = app_ctr.frame_buf.size();
+    let cell = if column < size.x && row < size.y {
+        Some(Point2 { x: column, y: row })
+    } else {
+        None
+    };
+    app_ctr.mouse_moved(cell);
+    match event {
+        MouseEvent::Down(button, ..) => app_ctr.mouse_down(translate_button(button)),
+        MouseEvent::Up(button, ..) => app_ctr.mouse_up(translate_button(button)),
+        MouseEvent::Drag(..) => (),
+        MouseEvent::ScrollDown(..) => app_ctr.mouse_wheel(Point2 { x: 0.0, y: -1.0 }),
+        MouseEvent::ScrollUp(..) => app_ctr.mouse_wheel(Point2 { x: 0.0, y: 1.0 }),
+    }
+}
+
+pub(crate) fn run<A>(app: A, color_mode: ColorMode, config: Config) -> Result<i32, Error>
+where
+    A: App,
+{
+    let mut app_ctr = AppContainer::new(app);
+    let result = run_loop(&mut app_ctr, color_mode, config);
+    // the terminal has been restored by now, so the app can print as it exits
+    let code = app_ctr.finish();
+    result.map(|()| code)
+}
+
+fn run_loop<A>(
+    app_ctr: &mut AppContainer<A>,
+    color_mode: ColorMode,
+    config: Config,
+) -> Result<(), Error>
+where
+    A: App,
+{
+    let mut term = Terminal::new(color_mode)?;
+    term.set_title(config.title_for::<A>())?;
+    let mut redraw = false;
+    app_ctr.start();
+    if let GridMode::FitWindow { .. } = A::GRID_MODE {
+        app_ctr.resize(term.size);
+    }
+    loop {
+        while event::poll(Duration::from_millis(0)).map_err(to_io)? {
+            match event::read().map_err(to_io)? {
+                // Raw mode swallows the interrupt signal, so treat ctrl-c like closing the window.
+                Event::Key(KeyEvent {
+                    code: TermKeyCode::Char('c'),
+                    modifiers,
+                }) if modifiers.contains(KeyModifiers::CONTROL) => app_ctr.close_requested(),
+                Event::Key(KeyEvent { code, modifiers }) => {
+                    if let Some(keycode) = translate_key(code) {
+                        let modifiers = translate_modifiers(code, modifiers);
+                        // Terminals only report presses, so release straight away. This means
+                        // keys are never seen as held down.
+                        app_ctr.key_down(keycode, Some(modifiers));
+                        app_ctr.key_up(keycode, Some(modifiers));
+                    }
+                    // The terminal has already applied the keyboard layout (and any input
+                    // method), so characters can be passed on as text.
+                    if let TermKeyCode::Char(ch) = code {
+                        if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+                            app_ctr.text_input(ch);
+                        }
+                    }
+                }
+                Event::Resize(..) => {
+                    term.invalidate()?;
+                    if let GridMode::FitWindow { .. } = A::GRID_MODE {
+                        app_ctr.resize(term.size);
+                    }
+                    redraw = true;
+                }
+                Event::Mouse(event) => handle_mouse(app_ctr, event),
+            }
+        }
+        if app_ctr.should_exit() {
+            return Ok(());
+        }
+        if app_ctr.run_due_updates(Instant::now()) || redraw {
+            term.draw(&app_ctr.frame_buf)?;
+            redraw = false;
+        }
+        let now = Instant::now();
+        let timeout = match app_ctr.next_update(now) {
+            Some(when) if when > now => when - now,
+            Some(_) => Duration::from_millis(0),
+            None => IDLE_WAIT,
+        };
+        event::poll(timeout).map_err(to_io)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ansi256_colors() {
+        let tests = [
+            // the corners of the color cube
+            ((0, 0, 0), 16),
+            ((255, 255, 255), 231),
+            ((255, 0, 0), 196),
+            ((0, 0, 255), 21),
+            // grays that are exactly in the cube stay there
+            ((95, 95, 95), 59),
+            // other grays use the ramp, which runs from 8 to 238
+            ((8, 8, 8), 232),
+            ((128, 128, 128), 244),
+            ((238, 238, 238), 255),
+            ((100, 100, 110), 241),
+            // cube levels are 0, 95, 135, 175, 215, 255
+            ((114, 0, 0), 52),
+            ((115, 0, 0), 88),
+            ((154, 0, 0), 88),
+            ((155, 0, 0), 124),
+            ((200, 100, 100), 167),
+        ];
+        for &((r, g, b), idx) in &tests {
+            assert_eq!(ansi256(r, g, b), idx, "({}, {}, {})", r, g, b);
+        }
+    }
+
+    #[test]
+    fn ansi16_colors() {
+        let tests = [
+            ((0, 0, 0), 0),
+            ((255, 255, 255), 15),
+            ((200, 10, 10), 1),
+            ((250, 20, 20), 9),
+            ((120, 120, 130), 8),
+            ((240, 240, 240), 7),
+            ((100, 100, 240), 12),
+            ((20, 180, 190), 6),
+        ];
+        for &((r, g, b), idx) in &tests {
+            assert_eq!(ansi16(r, g, b), idx, "({}, {}, {})", r, g, b);
+        }
+    }
+
+    #[test]
+    fn resolving_transparency() {
+        let ch = |fg, bg| Char { ch: 'x', fg, bg };
+        let half_white = Color::WHITE.with_alpha(128);
+        let tests = [
+            (ch(Color::TRANSPARENT, Color::TRANSPARENT), (None, None)),
+            (
+                ch(Color::rgb(10, 20, 30), Color::TRANSPARENT),
+                (Some([10, 20, 30]), None),
+            ),
+            (
+                ch(Color::TRANSPARENT, Color::BLUE),
+                (None, Some([0, 0, 255])),
+            ),
+            // translucent colors are drawn over black, or over the background
+            (
+                ch(Color::TRANSPARENT, Color::RED.with_alpha(128)),
+                (None, Some([128, 0, 0])),
+            ),
+            (ch(half_white, Color::TRANSPARENT), (Some([128; 3]), None)),
+            (
+                ch(half_white, Color::rgb(0, 0, 200)),
+                (Some([128, 128, 228]), Some([0, 0, 200])),
+            ),
+        ];
+        for (ch, colors) in &tests {
+            assert_eq!(resolve(ch), *colors, "{:?}", ch);
+        }
+    }
+
+    #[test]
+    fn translating_keys() {
+        let tests = [
+            (TermKeyCode::Up, Some(KeyCode::Up)),
+            (TermKeyCode::Down, Some(KeyCode::Down)),
+            (TermKeyCode::Left, Some(KeyCode::Left)),
+            (TermKeyCode::Right, Some(KeyCode::Right)),
+            (TermKeyCode::F(1), Some(KeyCode::F1)),
+            (TermKeyCode::F(12), Some(KeyCode::F12)),
+            (TermKeyCode::F(13), None),
+            (TermKeyCode::Char('c'), Some(KeyCode::C)),
+            (TermKeyCode::Char('C'), Some(KeyCode::C)),
+            (TermKeyCode::Char('5'), Some(KeyCode::Key5)),
+            (TermKeyCode::Char('é'), None),
+            (TermKeyCode::BackTab, Some(KeyCode::Tab)),
+            (TermKeyCode::Null, None),
+        ];
+        for &(code, key) in &tests {
+            assert_eq!(translate_key(code), key, "{:?}", code);
+        }
+    }
+
+    #[test]
+    fn translating_modifiers() {
+        let ctrl_c = translate_modifiers(TermKeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert!(ctrl_c.ctrl && !ctrl_c.shift && !ctrl_c.alt);
+        // shifted keys count as shifted whether or not the terminal says so
+        for &code in &[TermKeyCode::Char('C'), TermKeyCode::BackTab] {
+            let mods = translate_modifiers(code, KeyModifiers::empty());
+            assert!(mods.shift && !mods.ctrl, "{:?}", code);
+        }
+        let mods = translate_modifiers(TermKeyCode::Up, KeyModifiers::SHIFT | KeyModifiers::ALT);
+        assert!(mods.shift && mods.alt && !mods.ctrl);
+        assert_eq!(
+            translate_modifiers(TermKeyCode::Up, KeyModifiers::empty()),
+            Modifiers::default()
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! Borders around panels, with built-in styles and optional titles.
+use crate::{geom::draw_patch, Align, Char, Color, Frame, Rect};
+use mint::Point2;
+
+/// The characters used to draw a border.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BorderStyle {
+    pub top_left: char,
+    pub top: char,
+    pub top_right: char,
+    pub left: char,
+    pub right: char,
+    pub bottom_left: char,
+    pub bottom: char,
+    pub bottom_right: char,
+}
+
+impl BorderStyle {
+    /// `┌─┐`
+    pub const SINGLE: BorderStyle = BorderStyle::new(['┌', '─', '┐', '│', '│', '└', '─', '┘']);
+    /// `╔═╗`
+    pub const DOUBLE: BorderStyle = BorderStyle::new(['╔', '═', '╗', '║', '║', '╚', '═', '╝']);
+    /// `╭─╮`
+    pub const ROUNDED: BorderStyle = BorderStyle::new(['╭', '─', '╮', '│', '│', '╰', '─', '╯']);
+    /// `┏━┓`
+    pub const HEAVY: BorderStyle = BorderStyle::new(['┏', '━', '┓', '┃', '┃', '┗', '━', '┛']);
+    /// `+-+`, for fonts and terminals without box-drawing characters.
+    pub const ASCII: BorderStyle = BorderStyle::new(['+', '-', '+', '|', '|', '+', '-', '+']);
+
+    /// A style from its characters, in the order top-left, top, top-right, left, right,
+    /// bottom-left, bottom, bottom-right.
+    pub const fn new(chars: [char; 8]) -> Self {
+        BorderStyle {
+            top_left: chars[0],
+            top: chars[1],
+            top_right: chars[2],
+            left: chars[3],
+            right: chars[4],
+            bottom_left: chars[5],
+            bottom: chars[6],
+            bottom_right: chars[7],
+        }
+    }
+}
+
+impl Default for BorderStyle {
+    fn default() -> Self {
+        BorderStyle::SINGLE
+    }
+}
+
+/// A border around a panel, like a `NinePatch` set up at runtime.
+///
+/// Build one with `Border::new` and the builder methods, then `draw` it:
+///
+/// ```ignore
+/// Border::new(BorderStyle::ROUNDED)
+///     .title(" Inventory ", Align::Center)
+///     .fill(Color::BLACK)
+///     .draw(frame, rect);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Border {
+    style: BorderStyle,
+    fg: Color,
+    bg: Color,
+    fill: Option<Color>,
+    title: Option<(String, Align)>,
+    footer: Option<(String, Align)>,
+}
+
+impl Border {
+    /// A white border in the given style, with a transparent background and no title.
+    pub fn new(style: BorderStyle) -> Self {
+        Border {
+            style,
+            fg: Color::WHITE,
+            bg: Color::TRANSPARENT,
+            fill: None,
+            title: None,
+            footer: None,
+        }
+    }
+
+    /// The color of the border, title and footer.
+    pub fn fg(mut self, fg: Color) -> Self {
+        self.fg = fg;
+        self
+    }
+
+    /// The background color of the border, title and footer.
+    pub fn bg(mut self, bg: Color) -> Self {
+        self.bg = bg;
+        self
+    }
+
+    /// Clear the middle of the panel to blank cells with this background. By default the middle
+    /// is left untouched.
+    pub fn fill(mut self, bg: Color) -> Self {
+        self.fill = Some(bg);
+        self
+    }
+
+    /// Text to show in the top edge, cut off if it doesn't fit between the corners.
+    pub fn title(mut self, title: impl Into<String>, align: Align) -> Self {
+        self.title = Some((title.into(), align));
+        self
+    }
+
+    /// Text to show in the bottom edge, cut off if it doesn't fit between the corners.
+    pub fn footer(mut self, footer: impl Into<String>, align: Align) -> Self {
+        self.footer = Some((footer.into(), align));
+        self
+    }
+
+    /// Draw the border around the inside edge of `rect`, clipped to the frame. Box-drawing
+    /// characters are joined onto and connected to any lines already there, like
+    /// `NinePatch::draw`.
+    pub fn draw(&self, frame: &mut Frame, rect: Rect) {
+        let tile = |ch| Char {
+            ch,
+            fg: self.fg,
+            bg: self.bg,
+        };
+        let BorderStyle {
+            top_left,
+            top,
+            top_right,
+            left,
+            right,
+            bottom_left,
+            bottom,
+            bottom_right,
+        } = self.style;
+        let edges = [
+            tile(top_left),
+            tile(top),
+            tile(top_right),
+            tile(left),
+            tile(right),
+            tile(bottom_left),
+            tile(bottom),
+            tile(bottom_right),
+        ];
+        let middle = self.fill.map(|bg| Char {
+            ch: ' ',
+            fg: Color::TRANSPARENT,
+            bg,
+        });
+        draw_patch(frame, rect, edges, middle);
+
+        if rect.height() == 0 {
+            return;
+        }
+        let mut view = frame.view(rect);
+        // between the corners
+        let inner = Rect::from_parts(1, 0, rect.width().saturating_sub(1), 1);
+        if let Some((title, align)) = &self.title {
+            view.view(inner).print(
+                Point2 {
+                    x: indent(inner.width(), title, *align),
+                    y: 0,
+                },
+                title,
+                self.fg,
+                self.bg,
+            );
+        }
+        if let Some((footer, align)) = &self.footer {
+            let bottom = Rect::from_size(
+                Point2 {
+                    x: 1,
+                    y: rect.height() - 1,
+                },
+                inner.size(),
+            );
+            view.view(bottom).print(
+                Point2 {
+                    x: indent(inner.width(), footer, *align),
+                    y: 0,
+                },
+                footer,
+                self.fg,
+                self.bg,
+            );
+        }
+    }
+}
+
+/// How far in from the left to print `text` so it is aligned in `width` cells. Text that is too
+/// wide starts at the left.
+fn indent(width: usize, text: &str, align: Align) -> usize {
+    let spare = width.saturating_sub(crate::text::width(text));
+    match align {
+        Align::Left => 0,
+        Align::Center => spare / 2,
+        Align::Right => spare,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing::Harness, App, Context};
+
+    /// Draws each border over a background of dots.
+    struct Panels(Vec<(Border, Rect)>);
+
+    impl App for Panels {
+        const NAME: &'static str = "panels";
+        const SIZE: Point2<usize> = Point2 { x: 12, y: 4 };
+
+        fn update(&mut self, _ctx: Context<'_>, frame: &mut Frame) {
+            frame.view(frame.rect()).fill(Char {
+                ch: '.',
+                fg: Color::GRAY,
+                bg: Color::BLACK,
+            });
+            for (border, rect) in &self.0 {
+                border.draw(frame, *rect);
+            }
+        }
+    }
+
+    fn draw(borders: Vec<(Border, Rect)>) -> Harness<Panels> {
+        let mut harness = Harness::new(Panels(borders));
+        harness.tick();
+        harness
+    }
+
+    fn panel(left: usize, top: usize, right: usize, bottom: usize) -> Rect {
+        Rect::from_parts(left, top, right, bottom)
+    }
+
+    #[test]
+    fn titles_and_footers_are_aligned() {
+        let tests = [
+            (Align::Left, "┌ab────────┐", "└cde───────┘"),
+            (Align::Center, "┌────ab────┐", "└───cde────┘"),
+            (Align::Right, "┌────────ab┐", "└───────cde┘"),
+        ];
+        for &(align, top, bottom) in &tests {
+            let border = Border::new(BorderStyle::SINGLE)
+                .title("ab", align)
+                .footer("cde", align);
+            let harness = draw(vec![(border, panel(0, 0, 12, 4))]);
+            harness.assert_screen(&format!("{}\n│..........│\n│..........│\n{}", top, bottom));
+        }
+    }
+
+    #[test]
+    fn long_titles_are_cut_off_between_the_corners() {
+        let border = Border::new(BorderStyle::DOUBLE)
+            .title("a title that is too long", Align::Center)
+            .footer("漢字漢字", Align::Right);
+        let harness = draw(vec![(border, panel(2, 0, 9, 3))]);
+        harness.assert_screen("..╔a tit╗...\n..║.....║...\n..╚漢字 ╝...\n............");
+        // narrower than the corners
+        let border = Border::new(BorderStyle::SINGLE).title("abc", Align::Left);
+        let harness = draw(vec![(border, panel(0, 0, 2, 2))]);
+        harness.assert_screen("┌┐..........\n└┘..........\n............\n............");
+        let border = Border::new(BorderStyle::SINGLE).title("abc", Align::Left);
+        let harness = draw(vec![(border, panel(0, 0, 3, 1))]);
+        harness.assert_screen("├a┤.........\n............\n............\n............");
+    }
+
+    #[test]
+    fn filling_the_middle() {
+        let theme = Border::new(BorderStyle::ASCII)
+            .fg(Color::RED)
+            .bg(Color::BLUE)
+            .title("x", Align::Left);
+        let harness = draw(vec![
+            (theme.clone(), panel(0, 0, 5, 4)),
+            (theme.fill(Color::GREEN), panel(5, 0, 10, 4)),
+        ]);
+        harness.assert_screen("+x--++x--+..\n|...||   |..\n|...||   |..\n+---++---+..");
+        // the middle is left alone unless it is filled
+        assert_eq!(harness.cell(1, 1).bg, Color::BLACK);
+        assert_eq!(harness.cell(6, 1).bg, Color::GREEN);
+        assert_eq!(harness.cell(8, 2).bg, Color::GREEN);
+        // the border and title get the border's colors
+        for &(x, y) in &[(0, 0), (1, 0), (4, 2), (9, 3)] {
+            assert_eq!(harness.cell(x, y).fg, Color::RED, "({}, {})", x, y);
+            assert_eq!(harness.cell(x, y).bg, Color::BLUE, "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn empty_rects_draw_nothing() {
+        let border = Border::new(BorderStyle::SINGLE)
+            .title("a", Align::Left)
+            .footer("b", Align::Left)
+            .fill(Color::RED);
+        let harness = draw(vec![
+            (border.clone(), panel(1, 1, 1, 3)),
+            (border, panel(1, 1, 5, 1)),
+        ]);
+        harness.assert_screen("............\n............\n............\n............");
+    }
+}
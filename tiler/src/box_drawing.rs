@@ -0,0 +1,518 @@
+//! Joining box-drawing characters into junctions.
+//!
+//! Each box-drawing character is a set of arms going up, right, down and left from the middle of
+//! the cell, each single or double. When a line is drawn over another, e.g. with
+//! `Frame::set_joined`, their arms are combined and the character with those arms is used, so
+//! `║` drawn over `═` gives `╬`, and `╗` drawn over `╔` gives `╦`.
+//!
+//! Lines are also joined where they meet in neighbouring cells, with `Frame::set_connected`: a
+//! `─` drawn just right of a `│` turns it into `├`, so a corridor joins the wall of a room.
+//!
+//! Single and double lines can be mixed, as long as the two arms on each axis are the same style.
+//! Where they aren't, that axis is drawn double, since there is no character for it.
+use crate::{CellPos, Char, Frame, FrameView, TileSet};
+use mint::Point2;
+
+/// The style of an arm of a box-drawing character.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LineStyle {
+    Single,
+    Double,
+}
+
+/// The arms of a box-drawing character.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BoxChar {
+    pub up: Option<LineStyle>,
+    pub right: Option<LineStyle>,
+    pub down: Option<LineStyle>,
+    pub left: Option<LineStyle>,
+}
+
+/// Every box-drawing character we know about, with its arms as `[up, right, down, left]`, where
+/// 0 is no arm, 1 is single and 2 is double.
+const CHARS: &[(char, [u8; 4])] = &[
+    ('─', [0, 1, 0, 1]),
+    ('│', [1, 0, 1, 0]),
+    ('┌', [0, 1, 1, 0]),
+    ('┐', [0, 0, 1, 1]),
+    ('└', [1, 1, 0, 0]),
+    ('┘', [1, 0, 0, 1]),
+    ('├', [1, 1, 1, 0]),
+    ('┤', [1, 0, 1, 1]),
+    ('┬', [0, 1, 1, 1]),
+    ('┴', [1, 1, 0, 1]),
+    ('┼', [1, 1, 1, 1]),
+    ('╴', [0, 0, 0, 1]),
+    ('╵', [1, 0, 0, 0]),
+    ('╶', [0, 1, 0, 0]),
+    ('╷', [0, 0, 1, 0]),
+    ('═', [0, 2, 0, 2]),
+    ('║', [2, 0, 2, 0]),
+    ('╔', [0, 2, 2, 0]),
+    ('╗', [0, 0, 2, 2]),
+    ('╚', [2, 2, 0, 0]),
+    ('╝', [2, 0, 0, 2]),
+    ('╠', [2, 2, 2, 0]),
+    ('╣', [2, 0, 2, 2]),
+    ('╦', [0, 2, 2, 2]),
+    ('╩', [2, 2, 0, 2]),
+    ('╬', [2, 2, 2, 2]),
+    ('╒', [0, 2, 1, 0]),
+    ('╓', [0, 1, 2, 0]),
+    ('╕', [0, 0, 1, 2]),
+    ('╖', [0, 0, 2, 1]),
+    ('╘', [1, 2, 0, 0]),
+    ('╙', [2, 1, 0, 0]),
+    ('╛', [1, 0, 0, 2]),
+    ('╜', [2, 0, 0, 1]),
+    ('╞', [1, 2, 1, 0]),
+    ('╟', [2, 1, 2, 0]),
+    ('╡', [1, 0, 1, 2]),
+    ('╢', [2, 0, 2, 1]),
+    ('╤', [0, 2, 1, 2]),
+    ('╥', [0, 1, 2, 1]),
+    ('╧', [1, 2, 0, 2]),
+    ('╨', [2, 1, 0, 1]),
+    ('╪', [1, 2, 1, 2]),
+    ('╫', [2, 1, 2, 1]),
+];
+
+fn to_style(arm: u8) -> Option<LineStyle> {
+    match arm {
+        1 => Some(LineStyle::Single),
+        2 => Some(LineStyle::Double),
+        _ => None,
+    }
+}
+
+impl BoxChar {
+    /// The arms of `ch`, if it is a single or double box-drawing character.
+    pub fn from_char(ch: char) -> Option<Self> {
+        CHARS
+            .iter()
+            .find(|&&(c, _)| c == ch)
+            .map(|&(_, [up, right, down, left])| BoxChar {
+                up: to_style(up),
+                right: to_style(right),
+                down: to_style(down),
+                left: to_style(left),
+            })
+    }
+
+    /// The character that best matches these arms, or `None` if there are no arms.
+    pub fn to_char(self) -> Option<char> {
+        let BoxChar {
+            up,
+            right,
+            down,
+            left,
+        } = self;
+        let (up, down) = same_style(up, down);
+        let (left, right) = same_style(left, right);
+        let mut arms = BoxChar {
+            up,
+            right,
+            down,
+            left,
+        };
+        if let Some(ch) = arms.find() {
+            return Some(ch);
+        }
+        // there are no double half-lines, so use a whole one
+        match (up.or(down), left.or(right)) {
+            (Some(style), None) => {
+                arms.up = Some(style);
+                arms.down = Some(style);
+            }
+            (None, Some(style)) => {
+                arms.left = Some(style);
+                arms.right = Some(style);
+            }
+            _ => (),
+        }
+        arms.find()
+    }
+
+    /// The arms of both characters. Where both have an arm, `other`'s style is used.
+    pub fn merge(self, other: BoxChar) -> BoxChar {
+        BoxChar {
+            up: other.up.or(self.up),
+            right: other.right.or(self.right),
+            down: other.down.or(self.down),
+            left: other.left.or(self.left),
+        }
+    }
+
+    /// The arm going up (0), right (1), down (2) or left (3).
+    fn arm(&mut self, dir: usize) -> &mut Option<LineStyle> {
+        match dir {
+            0 => &mut self.up,
+            1 => &mut self.right,
+            2 => &mut self.down,
+            _ => &mut self.left,
+        }
+    }
+
+    /// The character with exactly these arms.
+    fn find(self) -> Option<char> {
+        CHARS
+            .iter()
+            .find(|&&(ch, _)| BoxChar::from_char(ch) == Some(self))
+            .map(|&(ch, _)| ch)
+    }
+}
+
+/// Make two opposite arms the same style, if they are both there.
+fn same_style(
+    a: Option<LineStyle>,
+    b: Option<LineStyle>,
+) -> (Option<LineStyle>, Option<LineStyle>) {
+    match (a, b) {
+        (Some(a), Some(b)) if a != b => (Some(LineStyle::Double), Some(LineStyle::Double)),
+        arms => arms,
+    }
+}
+
+/// The character you get by drawing `over` on top of `under`. If both are box-drawing characters
+/// they are joined, otherwise it is just `over`.
+pub fn join(under: char, over: char) -> char {
+    match (BoxChar::from_char(under), BoxChar::from_char(over)) {
+        (Some(under), Some(over)) => under.merge(over).to_char().unwrap_or(' '),
+        _ => over,
+    }
+}
+
+impl Frame {
+    /// Set a cell like `set`, but if the cell already holds a box-drawing character and `tile` is
+    /// one too, join them into a junction.
+    pub fn set_joined(&mut self, pos: impl CellPos, tile: impl TileSet) {
+        let mut ch = tile.to_char();
+        if let Some(cell) = self.try_get_mut(pos) {
+            ch.ch = join(cell.ch, ch.ch);
+            *cell = ch;
+        }
+    }
+
+    /// Set a cell like `set_joined`, and connect it to the box-drawing characters in the cells
+    /// next to it.
+    ///
+    /// Where `tile` has an arm pointing at a neighbouring line, that line grows an arm back, and
+    /// where a neighbouring line points at this cell, `tile` grows an arm towards it. So a `─`
+    /// drawn just right of a `│` makes it `├`, and a `│` drawn just below a `─` makes it `┬`.
+    /// Lines that run alongside each other, like the walls of two boxes side by side, are not
+    /// joined.
+    pub fn set_connected(&mut self, pos: impl CellPos, tile: impl TileSet) {
+        if let Some(pos) = pos.to_cell() {
+            connect(self, pos, tile.to_char());
+        }
+    }
+}
+
+impl FrameView<'_> {
+    /// Set a cell like `set`, joining box-drawing characters like `Frame::set_joined`.
+    pub fn set_joined(&mut self, pos: impl CellPos, tile: impl TileSet) {
+        if let Some(pos) = pos.to_cell() {
+            let mut ch = tile.to_char();
+            ch.ch = join(self[pos].ch, ch.ch);
+            self.set(pos, ch);
+        }
+    }
+
+    /// Set a cell like `set_joined`, and connect it to the lines around it like
+    /// `Frame::set_connected`. Cells outside the view are left alone.
+    pub fn set_connected(&mut self, pos: impl CellPos, tile: impl TileSet) {
+        if let Some(pos) = pos.to_cell() {
+            connect(self, pos, tile.to_char());
+        }
+    }
+}
+
+/// What `connect` needs to read and write cells, so it works on frames and views.
+trait Cells {
+    /// The cell at `pos`, if it can be drawn to.
+    fn cell(&self, pos: Point2<usize>) -> Option<Char>;
+    fn set_cell(&mut self, pos: Point2<usize>, ch: Char);
+}
+
+impl Cells for Frame {
+    fn cell(&self, pos: Point2<usize>) -> Option<Char> {
+        self.try_get(pos).copied()
+    }
+
+    fn set_cell(&mut self, pos: Point2<usize>, ch: Char) {
+        self.set(pos, ch);
+    }
+}
+
+impl Cells for FrameView<'_> {
+    fn cell(&self, pos: Point2<usize>) -> Option<Char> {
+        // cells that can't be seen read as blank, which never connects
+        Some(self[pos])
+    }
+
+    fn set_cell(&mut self, pos: Point2<usize>, ch: Char) {
+        self.set(pos, ch);
+    }
+}
+
+/// Set the cell at `pos` to `ch`, joined onto what's there and connected to its neighbours.
+fn connect(cells: &mut impl Cells, pos: Point2<usize>, mut ch: Char) {
+    let under = match cells.cell(pos) {
+        Some(under) => under,
+        None => return,
+    };
+    ch.ch = join(under.ch, ch.ch);
+    let mut arms = match BoxChar::from_char(ch.ch) {
+        Some(arms) => arms,
+        None => return cells.set_cell(pos, ch),
+    };
+    // up, right, down, left
+    let neighbours = [
+        pos.y.checked_sub(1).map(|y| Point2 { x: pos.x, y }),
+        Some(Point2 {
+            x: pos.x + 1,
+            y: pos.y,
+        }),
+        Some(Point2 {
+            x: pos.x,
+            y: pos.y + 1,
+        }),
+        pos.x.checked_sub(1).map(|x| Point2 { x, y: pos.y }),
+    ];
+    for (dir, neighbour) in neighbours.iter().enumerate() {
+        let (npos, mut next) = match neighbour.and_then(|npos| Some((npos, cells.cell(npos)?))) {
+            Some(found) => found,
+            None => continue,
+        };
+        let mut next_arms = match BoxChar::from_char(next.ch) {
+            Some(next_arms) => next_arms,
+            None => continue,
+        };
+        let back = (dir + 2) % 4;
+        match (*arms.arm(dir), *next_arms.arm(back)) {
+            (Some(style), None) => {
+                *next_arms.arm(back) = Some(style);
+                if let Some(joined) = next_arms.to_char() {
+                    next.ch = joined;
+                    cells.set_cell(npos, next);
+                }
+            }
+            (None, Some(style)) => *arms.arm(dir) = Some(style),
+            _ => (),
+        }
+    }
+    ch.ch = arms.to_char().unwrap_or(ch.ch);
+    cells.set_cell(pos, ch);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{row, screen},
+        Border, BorderStyle, Rect,
+    };
+    use LineStyle::*;
+
+    fn frame_of(rows: &[&str]) -> Frame {
+        let width = rows[0].chars().count();
+        let mut frame = Frame::new(Point2 {
+            x: width,
+            y: rows.len(),
+        });
+        for (y, row) in rows.iter().enumerate() {
+            for (x, ch) in row.chars().enumerate() {
+                frame[(x, y)].ch = ch;
+            }
+        }
+        frame
+    }
+
+    fn line(ch: char) -> Char {
+        Char {
+            ch,
+            ..Char::default()
+        }
+    }
+
+    #[test]
+    fn every_char_round_trips() {
+        for &(ch, _) in CHARS {
+            assert_eq!(BoxChar::from_char(ch).and_then(BoxChar::to_char), Some(ch));
+        }
+        assert_eq!(BoxChar::from_char('a'), None);
+        assert_eq!(BoxChar::from_char(' '), None);
+    }
+
+    #[test]
+    fn to_char() {
+        let arms = |up, right, down, left| BoxChar {
+            up,
+            right,
+            down,
+            left,
+        };
+        let tests = [
+            (arms(None, None, None, None), None),
+            (arms(None, None, None, Some(Single)), Some('╴')),
+            // there are no double half-lines
+            (arms(Some(Double), None, None, None), Some('║')),
+            (arms(None, Some(Double), None, None), Some('═')),
+            // mismatched styles on one axis are drawn double
+            (arms(Some(Single), None, Some(Double), None), Some('║')),
+            (
+                arms(Some(Double), Some(Single), Some(Single), Some(Single)),
+                Some('╫'),
+            ),
+            (
+                arms(Some(Single), Some(Double), Some(Single), None),
+                Some('╞'),
+            ),
+        ];
+        for &(arms, expected) in tests.iter() {
+            assert_eq!(arms.to_char(), expected, "{:?}", arms);
+        }
+    }
+
+    #[test]
+    fn merge_prefers_the_top_style() {
+        let single = BoxChar::from_char('─').unwrap();
+        let double = BoxChar::from_char('╦').unwrap();
+        assert_eq!(single.merge(double).to_char(), Some('╦'));
+        assert_eq!(double.merge(single).to_char(), Some('╥'));
+    }
+
+    #[test]
+    fn join() {
+        let tests = [
+            ('│', '─', '┼'),
+            ('║', '═', '╬'),
+            ('┌', '┐', '┬'),
+            ('╔', '╗', '╦'),
+            ('┌', '┘', '┼'),
+            ('├', '┤', '┼'),
+            ('│', '═', '╪'),
+            ('─', '║', '╫'),
+            ('│', '╓', '╟'),
+            ('╷', '╵', '│'),
+            ('│', '│', '│'),
+            ('a', '─', '─'),
+            ('─', 'a', 'a'),
+            (' ', '┐', '┐'),
+        ];
+        for &(under, over, expected) in tests.iter() {
+            assert_eq!(
+                super::join(under, over),
+                expected,
+                "{:?} under {:?}",
+                under,
+                over
+            );
+        }
+    }
+
+    #[test]
+    fn set_joined_only_looks_at_the_cell() {
+        let mut frame = frame_of(&["│ ", "│ "]);
+        frame.set_joined((1, 0), line('─'));
+        frame.set_joined((0, 1), line('─'));
+        assert_eq!(screen(&frame), "│─\n┼ ");
+    }
+
+    /// The frame before, where to draw, what to draw and the frame after.
+    type Case = (
+        &'static [&'static str],
+        (usize, usize),
+        char,
+        &'static [&'static str],
+    );
+
+    #[test]
+    fn set_connected() {
+        let tests: &[Case] = &[
+            // the line drawn next to grows an arm
+            (&["│ ", "│ ", "│ "], (1, 1), '─', &["│ ", "├─", "│ "]),
+            (&["───", "   "], (1, 1), '│', &["─┬─", " │ "]),
+            (&["═══", "   "], (1, 1), '│', &["═╤═", " │ "]),
+            // the new line grows an arm towards lines pointing at it
+            (&["─ ", "  "], (1, 0), '│', &["─┤", "  "]),
+            (&[" │", "  "], (1, 1), '─', &[" │", " ┴"]),
+            // lines running alongside don't join
+            (&["│ ", "│ "], (1, 0), '│', &["││", "│ "]),
+            (&["─ ", "  "], (0, 1), '─', &["─ ", "─ "]),
+            // other characters are left alone
+            (&["a ", "  "], (1, 0), '─', &["a─", "  "]),
+            (&["─ ", "  "], (1, 0), 'b', &["─b", "  "]),
+            // and it joins onto the cell like `set_joined`
+            (&[" │ ", "───", " │ "], (1, 1), '│', &[" │ ", "─┼─", " │ "]),
+        ];
+        for &(before, pos, ch, after) in tests {
+            let mut frame = frame_of(before);
+            frame.set_connected(pos, line(ch));
+            assert_eq!(
+                screen(&frame),
+                after.join("\n"),
+                "{:?} at {:?} in {:?}",
+                ch,
+                pos,
+                before
+            );
+        }
+    }
+
+    #[test]
+    fn set_connected_off_the_frame_does_nothing() {
+        let mut frame = frame_of(&["─"]);
+        frame.set_connected((1, 0), line('─'));
+        frame.set_connected((-1, 0), line('─'));
+        assert_eq!(screen(&frame), "─");
+    }
+
+    #[test]
+    fn views_only_connect_inside() {
+        let mut frame = frame_of(&["│  ", "│  "]);
+        let mut view = frame.view(Rect::from_parts(1, 0, 3, 2));
+        view.set_connected((0, 0), line('─'));
+        assert_eq!(screen(&frame), "│─ \n│  ");
+        let mut view = frame.view(Rect::from_parts(0, 1, 3, 2));
+        view.set_connected((1, 0), line('─'));
+        assert_eq!(screen(&frame), "│─ \n├─ ");
+    }
+
+    #[test]
+    fn corridors_join_rooms() {
+        let mut frame = Frame::new(Point2 { x: 8, y: 3 });
+        for x in 6..8 {
+            frame.set_connected((x, 1), line('─'));
+        }
+        Border::new(BorderStyle::SINGLE).draw(&mut frame, Rect::from_parts(0, 0, 6, 3));
+        assert_eq!(screen(&frame), "┌────┐  \n│    ├──\n└────┘  ");
+
+        let mut frame = Frame::new(Point2 { x: 6, y: 4 });
+        Border::new(BorderStyle::DOUBLE).draw(&mut frame, Rect::from_parts(0, 0, 6, 3));
+        frame.set_connected((2, 3), line('│'));
+        assert_eq!(screen(&frame), "╔════╗\n║    ║\n╚═╤══╝\n  │   ");
+    }
+
+    #[test]
+    fn boxes_side_by_side_stay_apart() {
+        let mut frame = Frame::new(Point2 { x: 6, y: 3 });
+        let border = Border::new(BorderStyle::SINGLE);
+        border.draw(&mut frame, Rect::from_parts(0, 0, 3, 3));
+        border.draw(&mut frame, Rect::from_parts(3, 0, 6, 3));
+        assert_eq!(screen(&frame), "┌─┐┌─┐\n│ ││ │\n└─┘└─┘");
+        // but boxes sharing a wall join up
+        border.draw(&mut frame, Rect::from_parts(0, 2, 4, 3));
+        assert_eq!(row(&frame, 2), "├─┴┼─┘");
+    }
+
+    #[test]
+    fn filled_middles_are_not_connected_to() {
+        let mut frame = frame_of(&["     ", " ─ ──", "     "]);
+        Border::new(BorderStyle::SINGLE)
+            .fill(crate::Color::BLACK)
+            .draw(&mut frame, Rect::from_parts(0, 0, 3, 3));
+        assert_eq!(screen(&frame), "┌─┐  \n│ ├──\n└─┘  ");
+    }
+}
@@ -0,0 +1,138 @@
+//! Settings for the window and runtime, passed to `run`.
+use crate::{
+    raster::{CELL_HEIGHT, CELL_WIDTH},
+    App, Backend, Color, GridMode, Image,
+};
+use mint::Point2;
+
+/// The size the window opens at.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WindowSize {
+    /// Let the platform choose.
+    Default,
+    /// Big enough for this many cells, at the grid mode's cell size (or 10x20 pixels for a fixed
+    /// grid).
+    Cells(Point2<u32>),
+    /// This many physical pixels.
+    Pixels(Point2<u32>),
+}
+
+/// How the app should be run.
+///
+/// Build one with `Config::default()` and the builder methods, e.g.
+///
+/// ```ignore
+/// tiler::run(app, Config::default().title("My game").vsync(false))
+/// ```
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub(crate) backend: Backend,
+    pub(crate) title: Option<String>,
+    pub(crate) icon: Option<Image>,
+    pub(crate) size: WindowSize,
+    pub(crate) resizable: bool,
+    pub(crate) fullscreen: bool,
+    pub(crate) vsync: bool,
+    pub(crate) clear_color: Color,
+    pub(crate) x11_class: Option<(String, String)>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            backend: Backend::Wgpu,
+            title: None,
+            icon: None,
+            size: WindowSize::Default,
+            resizable: true,
+            fullscreen: false,
+            vsync: true,
+            clear_color: Color::BLACK,
+            x11_class: None,
+        }
+    }
+}
+
+impl Config {
+    /// The backend to run the app with. Defaults to `Backend::Wgpu`.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// The window title. Defaults to `App::NAME`.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// The window icon.
+    pub fn icon(mut self, icon: Image) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// The size the window opens at.
+    pub fn size(mut self, size: WindowSize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Whether the user can resize the window. Defaults to `true`.
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Whether to start in borderless fullscreen. This can be changed later with
+    /// `Context::set_fullscreen`.
+    pub fn fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    /// Whether to wait for vertical sync before presenting a frame. Defaults to `true`.
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    /// The color drawn behind the grid, including any letterboxing bars. Defaults to black.
+    pub fn clear_color(mut self, color: impl Into<Color>) -> Self {
+        self.clear_color = color.into();
+        self
+    }
+
+    /// The X11 `WM_CLASS` of the window, as `(name, class)`. Tiling window managers often use
+    /// this to decide whether a window floats.
+    pub fn x11_class(mut self, name: impl Into<String>, class: impl Into<String>) -> Self {
+        self.x11_class = Some((name.into(), class.into()));
+        self
+    }
+
+    /// The title to use for `A`.
+    pub(crate) fn title_for<A: App>(&self) -> &str {
+        self.title.as_deref().unwrap_or(A::NAME)
+    }
+
+    /// The initial window size in physical pixels, if one was asked for.
+    pub(crate) fn pixel_size<A: App>(&self) -> Option<Point2<u32>> {
+        match self.size {
+            WindowSize::Default => None,
+            WindowSize::Pixels(size) => Some(size),
+            WindowSize::Cells(cells) => {
+                let cell = match A::GRID_MODE {
+                    GridMode::Fixed => Point2 {
+                        x: CELL_WIDTH,
+                        y: CELL_HEIGHT,
+                    },
+                    GridMode::FitWindow { cell_size } => cell_size,
+                };
+                Some(Point2 {
+                    x: cells.x * cell.x,
+                    y: cells.y * cell.y,
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! The errors that can stop an app from running.
+use std::{any::Any, error::Error as StdError, fmt, io};
+use winit::{error::OsError, window::BadIcon};
+
+/// Why `run` failed.
+#[derive(Debug)]
+pub enum Error {
+    /// No graphics adapter could be found.
+    NoAdapter(String),
+    /// The graphics adapter could not give us a device.
+    Device(String),
+    /// A surface to draw to could not be created for the window.
+    Surface(String),
+    /// The window could not be created.
+    Window(OsError),
+    /// The window icon was invalid.
+    Icon(BadIcon),
+    /// Drawing a frame failed.
+    Draw(String),
+    /// Reading from or writing to the terminal failed.
+    Io(io::Error),
+}
+
+impl Error {
+    /// Whether the error means the wgpu backend can't be used on this machine, so another backend
+    /// (e.g. the terminal) might work instead.
+    pub fn is_graphics_unavailable(&self) -> bool {
+        matches!(
+            self,
+            Error::NoAdapter(_) | Error::Device(_) | Error::Surface(_) | Error::Window(_)
+        )
+    }
+
+    /// Build an error from the payload of a panic caught in wgpu, which reports most failures
+    /// by panicking.
+    pub(crate) fn from_panic(make: fn(String) -> Error, payload: Box<dyn Any + Send>) -> Error {
+        let msg = if let Some(msg) = payload.downcast_ref::<&str>() {
+            msg.to_string()
+        } else if let Some(msg) = payload.downcast_ref::<String>() {
+            msg.clone()
+        } else {
+            "unknown error".to_string()
+        };
+        make(msg)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NoAdapter(msg) => write!(f, "no graphics adapter found: {}", msg),
+            Error::Device(msg) => write!(f, "could not get a graphics device: {}", msg),
+            Error::Surface(msg) => write!(f, "could not create a surface for the window: {}", msg),
+            Error::Window(e) => write!(f, "could not create the window: {}", e),
+            Error::Icon(e) => write!(f, "invalid window icon: {}", e),
+            Error::Draw(msg) => write!(f, "could not draw the frame: {}", msg),
+            Error::Io(e) => write!(f, "terminal i/o failed: {}", e),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Window(e) => Some(e),
+            Error::Icon(e) => Some(e),
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<OsError> for Error {
+    fn from(e: OsError) -> Self {
+        Error::Window(e)
+    }
+}
+
+impl From<BadIcon> for Error {
+    fn from(e: BadIcon) -> Self {
+        Error::Icon(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
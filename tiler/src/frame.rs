@@ -0,0 +1,319 @@
+use crate::{Color, Rect};
+use mint::Point2;
+use std::ops::{Index, IndexMut};
+
+#[derive(Debug, Clone)]
+pub struct Frame {
+    buf: Vec<Char>,
+    pub(crate) size: Point2<usize>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Char {
+    /// Get the character for this tile.
+    pub ch: char,
+    /// The foreground color of the character
+    pub fg: Color,
+    /// The background color of the character
+    pub bg: Color,
+}
+
+impl Default for Char {
+    fn default() -> Self {
+        Self {
+            ch: ' ',
+            fg: Color::TRANSPARENT,
+            bg: Color::TRANSPARENT,
+        }
+    }
+}
+
+impl Frame {
+    pub fn new(size: Point2<usize>) -> Self {
+        let area = size.x * size.y;
+        let mut buf = Vec::with_capacity(area);
+        for _ in 0..area {
+            buf.push(Default::default());
+        }
+        Self { buf, size }
+    }
+
+    pub fn clear(&mut self) {
+        for el in self.buf.iter_mut() {
+            *el = Default::default();
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, f: impl Fn(usize, usize) -> Char) -> Self {
+        let mut buf = Vec::with_capacity(width * height);
+        for x in 0..width {
+            for y in 0..height {
+                buf.push(f(x, y));
+            }
+        }
+        Self {
+            buf,
+            size: Point2 {
+                x: width,
+                y: height,
+            },
+        }
+    }
+
+    /// The size of the grid, in cells.
+    #[inline]
+    pub fn size(&self) -> Point2<usize> {
+        self.size
+    }
+
+    /// The rectangle covering the whole frame.
+    pub fn rect(&self) -> Rect {
+        Rect::from_size(Point2 { x: 0, y: 0 }, self.size)
+    }
+
+    /// Change the size of the grid. Cells inside both the old and new size keep their contents,
+    /// and any new cells are blank.
+    pub fn resize(&mut self, size: Point2<usize>) {
+        if size == self.size {
+            return;
+        }
+        let mut resized = Frame::new(size);
+        for x in 0..size.x.min(self.size.x) {
+            for y in 0..size.y.min(self.size.y) {
+                resized[(x, y)] = self[(x, y)];
+            }
+        }
+        *self = resized;
+    }
+
+    /// Get an item from the grid by location.
+    ///
+    /// You can also use the implementation of `Index` like so: `frame[(1, 2)]`. Like this method,
+    /// indexing only takes unsigned positions, so use `try_get` for signed ones.
+    ///
+    /// # Panics
+    ///
+    /// If the position is outside the frame. Use `try_get` if it might be.
+    #[inline]
+    pub fn get(&self, pos: Point2<usize>) -> &Char {
+        match self.idx(pos) {
+            Some(idx) => &self.buf[idx],
+            None => self.out_of_bounds(pos),
+        }
+    }
+
+    /// Get a mutable ref to and item from the grid by location.
+    ///
+    /// You can also use the implementation of `IndexMut` like so: `frame[(1, 2)] = 2`.
+    ///
+    /// # Panics
+    ///
+    /// If the position is outside the frame. Use `try_get_mut` or `set` if it might be.
+    #[inline]
+    pub fn get_mut(&mut self, pos: Point2<usize>) -> &mut Char {
+        match self.idx(pos) {
+            Some(idx) => &mut self.buf[idx],
+            None => self.out_of_bounds(pos),
+        }
+    }
+
+    /// Get an item from the grid by location, or `None` if it is outside the frame.
+    #[inline]
+    pub fn try_get(&self, pos: impl CellPos) -> Option<&Char> {
+        let idx = self.idx(pos.to_cell()?)?;
+        Some(&self.buf[idx])
+    }
+
+    /// Get a mutable ref to an item from the grid by location, or `None` if it is outside the
+    /// frame.
+    #[inline]
+    pub fn try_get_mut(&mut self, pos: impl CellPos) -> Option<&mut Char> {
+        let idx = self.idx(pos.to_cell()?)?;
+        Some(&mut self.buf[idx])
+    }
+
+    /// Set the item at a location, returning whether it is inside the frame. Nothing happens if
+    /// it isn't, so this can be used to draw things that are partly off screen.
+    #[inline]
+    pub fn set(&mut self, pos: impl CellPos, ch: Char) -> bool {
+        match self.try_get_mut(pos) {
+            Some(cell) => {
+                *cell = ch;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The index into `buf` of a position, if it is inside the frame.
+    fn idx(&self, Point2 { x, y }: Point2<usize>) -> Option<usize> {
+        if x < self.size.x && y < self.size.y {
+            Some(x * self.size.y + y)
+        } else {
+            None
+        }
+    }
+
+    fn out_of_bounds(&self, pos: Point2<usize>) -> ! {
+        panic!(
+            "position ({}, {}) is outside the frame (size {}x{})",
+            pos.x, pos.y, self.size.x, self.size.y
+        )
+    }
+
+    pub fn debug_print(&self) {
+        println!("Frame:");
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                print!("{}", self.get(Point2 { x, y }).ch);
+            }
+            println!()
+        }
+    }
+}
+
+/// A position of a cell in a `Frame`: a `Point2` or tuple of either `usize` or `i32`.
+///
+/// Signed positions let games work in map coordinates that can go negative. Negative positions
+/// are always outside the frame. Indexing only takes unsigned positions, so use `try_get` or
+/// `set` for signed ones.
+pub trait CellPos: Copy {
+    /// The position as unsigned coordinates, or `None` if it is negative.
+    fn to_cell(self) -> Option<Point2<usize>>;
+}
+
+impl CellPos for Point2<usize> {
+    fn to_cell(self) -> Option<Point2<usize>> {
+        Some(self)
+    }
+}
+
+impl CellPos for (usize, usize) {
+    fn to_cell(self) -> Option<Point2<usize>> {
+        Some(Point2 {
+            x: self.0,
+            y: self.1,
+        })
+    }
+}
+
+impl CellPos for Point2<i32> {
+    fn to_cell(self) -> Option<Point2<usize>> {
+        if self.x < 0 || self.y < 0 {
+            return None;
+        }
+        Some(Point2 {
+            x: self.x as usize,
+            y: self.y as usize,
+        })
+    }
+}
+
+impl CellPos for (i32, i32) {
+    fn to_cell(self) -> Option<Point2<usize>> {
+        Point2 {
+            x: self.0,
+            y: self.1,
+        }
+        .to_cell()
+    }
+}
+
+impl Index<(usize, usize)> for Frame {
+    type Output = Char;
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        &self[Point2 { x, y }]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Frame {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        &mut self[Point2 { x, y }]
+    }
+}
+
+impl Index<Point2<usize>> for Frame {
+    type Output = Char;
+    fn index(&self, pos: Point2<usize>) -> &Self::Output {
+        self.get(pos)
+    }
+}
+
+impl IndexMut<Point2<usize>> for Frame {
+    fn index_mut(&mut self, pos: Point2<usize>) -> &mut Self::Output {
+        self.get_mut(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(ch: char) -> Char {
+        Char {
+            ch,
+            ..Char::default()
+        }
+    }
+
+    /// A 3x2 frame with each cell holding a different letter, going along the rows.
+    fn letters() -> Frame {
+        Frame::from_fn(3, 2, |x, y| tile((b'a' + (y * 3 + x) as u8) as char))
+    }
+
+    #[test]
+    fn from_fn_gets_each_position() {
+        let frame = letters();
+        assert_eq!(frame.size(), Point2 { x: 3, y: 2 });
+        assert_eq!(frame[(0, 0)].ch, 'a');
+        assert_eq!(frame[(2, 0)].ch, 'c');
+        assert_eq!(frame[(0, 1)].ch, 'd');
+        assert_eq!(frame[Point2 { x: 2, y: 1 }].ch, 'f');
+    }
+
+    #[test]
+    fn positions_outside_the_frame_are_not_other_cells() {
+        let frame = letters();
+        let outside: &[(i32, i32)] = &[(-1, 0), (0, -1), (-1, -1), (3, 0), (0, 2), (1, 2), (3, 1)];
+        for &(x, y) in outside {
+            assert_eq!(frame.try_get((x, y)), None, "({}, {})", x, y);
+            assert_eq!(frame.try_get(Point2 { x, y }), None, "({}, {})", x, y);
+        }
+        // these used to wrap around into the next column
+        assert_eq!(frame.try_get((0usize, 2usize)), None);
+        assert_eq!(frame.try_get((2usize, 5usize)), None);
+        assert_eq!(frame.try_get((usize::MAX, 0usize)), None);
+        assert_eq!(frame.try_get((1, 1)).map(|c| c.ch), Some('e'));
+        assert_eq!(
+            frame.try_get(Point2 { x: 2usize, y: 0 }).map(|c| c.ch),
+            Some('c')
+        );
+    }
+
+    #[test]
+    fn set_reports_whether_the_cell_is_inside() {
+        let mut frame = letters();
+        for &(x, y) in &[(-1, 0), (0, -1), (3, 0), (0, 2), (100, 100)] {
+            assert!(!frame.set((x, y), tile('!')), "({}, {})", x, y);
+        }
+        assert_eq!(frame.buf.iter().filter(|c| c.ch == '!').count(), 0);
+        assert!(frame.set((2, 1), tile('!')));
+        assert_eq!(frame[(2, 1)].ch, '!');
+        assert_eq!(frame.buf.iter().filter(|c| c.ch == '!').count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "position (0, 2) is outside the frame (size 3x2)")]
+    fn indexing_outside_the_frame_panics() {
+        let _ = letters()[(0, 2)];
+    }
+
+    #[test]
+    fn resizing_keeps_the_overlap() {
+        let mut frame = letters();
+        frame.resize(Point2 { x: 2, y: 3 });
+        assert_eq!(frame[(1, 1)].ch, 'e');
+        assert_eq!(frame[(1, 2)], Char::default());
+        assert_eq!(frame.try_get((2, 0)), None);
+    }
+}
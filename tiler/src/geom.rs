@@ -0,0 +1,450 @@
+//! Rectangles of cells, and drawing nine-patches over them.
+use crate::{Char, Frame, TileSet};
+use mint::Point2;
+
+/// A rectangle of cells. `top_left` is inside the rectangle and `bottom_right` is just outside
+/// it, so a rectangle with `top_left == bottom_right` is empty.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Rect {
+    pub top_left: Point2<usize>,
+    pub bottom_right: Point2<usize>,
+}
+
+impl Rect {
+    /// Create a rectangle from its edges. `right` and `bottom` are exclusive.
+    pub fn from_parts(left: usize, top: usize, right: usize, bottom: usize) -> Self {
+        Self {
+            top_left: Point2 { x: left, y: top },
+            bottom_right: Point2 {
+                x: right,
+                y: bottom,
+            },
+        }
+    }
+
+    /// Create a rectangle from its top-left corner and size.
+    pub fn from_size(top_left: Point2<usize>, size: Point2<usize>) -> Self {
+        Self::from_parts(
+            top_left.x,
+            top_left.y,
+            top_left.x + size.x,
+            top_left.y + size.y,
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.bottom_right.x.saturating_sub(self.top_left.x)
+    }
+
+    pub fn height(&self) -> usize {
+        self.bottom_right.y.saturating_sub(self.top_left.y)
+    }
+
+    pub fn size(&self) -> Point2<usize> {
+        Point2 {
+            x: self.width(),
+            y: self.height(),
+        }
+    }
+
+    /// Whether the rectangle contains no cells.
+    pub fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
+    }
+
+    /// Whether the cell is inside the rectangle.
+    pub fn contains(&self, Point2 { x, y }: Point2<usize>) -> bool {
+        x >= self.top_left.x
+            && x < self.bottom_right.x
+            && y >= self.top_left.y
+            && y < self.bottom_right.y
+    }
+
+    /// The cells inside both rectangles. The result may be empty.
+    pub fn intersection(&self, other: Rect) -> Rect {
+        let left = self.top_left.x.max(other.top_left.x);
+        let top = self.top_left.y.max(other.top_left.y);
+        Rect::from_parts(
+            left,
+            top,
+            self.bottom_right.x.min(other.bottom_right.x).max(left),
+            self.bottom_right.y.min(other.bottom_right.y).max(top),
+        )
+    }
+
+    /// The smallest rectangle containing both rectangles. Empty rectangles are ignored.
+    pub fn union(&self, other: Rect) -> Rect {
+        if self.is_empty() {
+            return other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        Rect::from_parts(
+            self.top_left.x.min(other.top_left.x),
+            self.top_left.y.min(other.top_left.y),
+            self.bottom_right.x.max(other.bottom_right.x),
+            self.bottom_right.y.max(other.bottom_right.y),
+        )
+    }
+
+    /// Grow the rectangle by `by` cells on every side. It stops growing at 0 on the top and left.
+    pub fn inflate(&self, by: usize) -> Rect {
+        Rect::from_parts(
+            self.top_left.x.saturating_sub(by),
+            self.top_left.y.saturating_sub(by),
+            self.bottom_right.x + by,
+            self.bottom_right.y + by,
+        )
+    }
+
+    /// Shrink the rectangle by `by` cells on every side. If it is too small, the result is empty
+    /// (and in the middle of the rectangle).
+    pub fn deflate(&self, by: usize) -> Rect {
+        let x = shrink(self.top_left.x, self.bottom_right.x, by);
+        let y = shrink(self.top_left.y, self.bottom_right.y, by);
+        Rect::from_parts(x.0, y.0, x.1, y.1)
+    }
+
+    /// Cut the rectangle into a top part `at` rows high and a bottom part with the rest. `at` is
+    /// limited to the height of the rectangle.
+    pub fn split_horizontally(&self, at: usize) -> (Rect, Rect) {
+        let y = self.top_left.y + at.min(self.height());
+        (
+            Rect::from_parts(self.top_left.x, self.top_left.y, self.bottom_right.x, y),
+            Rect::from_parts(self.top_left.x, y, self.bottom_right.x, self.bottom_right.y),
+        )
+    }
+
+    /// Cut the rectangle into a left part `at` columns wide and a right part with the rest. `at`
+    /// is limited to the width of the rectangle.
+    pub fn split_vertically(&self, at: usize) -> (Rect, Rect) {
+        let x = self.top_left.x + at.min(self.width());
+        (
+            Rect::from_parts(self.top_left.x, self.top_left.y, x, self.bottom_right.y),
+            Rect::from_parts(x, self.top_left.y, self.bottom_right.x, self.bottom_right.y),
+        )
+    }
+
+    /// Like `split_horizontally`, with the top part taking `ratio` (between 0 and 1) of the
+    /// height, rounded to the nearest row.
+    pub fn split_horizontally_ratio(&self, ratio: f32) -> (Rect, Rect) {
+        self.split_horizontally(portion(self.height(), ratio))
+    }
+
+    /// Like `split_vertically`, with the left part taking `ratio` (between 0 and 1) of the width,
+    /// rounded to the nearest column.
+    pub fn split_vertically_ratio(&self, ratio: f32) -> (Rect, Rect) {
+        self.split_vertically(portion(self.width(), ratio))
+    }
+
+    /// Every cell in the rectangle, a row at a time from the top.
+    pub fn cells(&self) -> impl Iterator<Item = Point2<usize>> {
+        let Rect {
+            top_left,
+            bottom_right,
+        } = *self;
+        (top_left.y..bottom_right.y)
+            .flat_map(move |y| (top_left.x..bottom_right.x).map(move |x| Point2 { x, y }))
+    }
+
+    /// The cells around the inside edge of the rectangle, each once, going clockwise from the
+    /// top-left.
+    pub fn edge_cells(&self) -> impl Iterator<Item = Point2<usize>> {
+        let Point2 { x: left, y: top } = self.top_left;
+        let (width, height) = if self.is_empty() {
+            (0, 0)
+        } else {
+            (self.width(), self.height())
+        };
+        let (right, bottom) = (left + width, top + height);
+        let top_edge = (left..right).map(move |x| Point2 { x, y: top });
+        let right_edge = (top + 1..bottom).map(move |y| Point2 { x: right - 1, y });
+        let bottom_edge = if height > 1 { left..right - 1 } else { 0..0 }
+            .rev()
+            .map(move |x| Point2 { x, y: bottom - 1 });
+        let left_edge = if width > 1 { top + 1..bottom - 1 } else { 0..0 }
+            .rev()
+            .map(move |y| Point2 { x: left, y });
+        top_edge
+            .chain(right_edge)
+            .chain(bottom_edge)
+            .chain(left_edge)
+    }
+}
+
+/// Move both ends of the span `start..end` in by `by`, meeting in the middle if it is too short.
+fn shrink(start: usize, end: usize, by: usize) -> (usize, usize) {
+    if end.saturating_sub(start) >= 2 * by {
+        (start + by, end - by)
+    } else {
+        let middle = start + end.saturating_sub(start) / 2;
+        (middle, middle)
+    }
+}
+
+/// `ratio` of `len`, rounded.
+fn portion(len: usize, ratio: f32) -> usize {
+    (len as f32 * ratio.clamp(0.0, 1.0)).round() as usize
+}
+
+/// A way of drawing a box, from tiles for its corners, edges and middle.
+pub trait NinePatch {
+    type TileSet: TileSet;
+    const TOP_LEFT: Self::TileSet;
+    const TOP: Self::TileSet;
+    const TOP_RIGHT: Self::TileSet;
+    const LEFT: Self::TileSet;
+    const MIDDLE: Self::TileSet;
+    const RIGHT: Self::TileSet;
+    const BOTTOM_LEFT: Self::TileSet;
+    const BOTTOM: Self::TileSet;
+    const BOTTOM_RIGHT: Self::TileSet;
+    /// Whether `draw` fills the middle with `MIDDLE`. If not, the middle is left as it was, so
+    /// only the border is drawn.
+    const FILL_MIDDLE: bool = true;
+
+    /// Draw the patch over `rect`, clipped to the frame.
+    ///
+    /// Box-drawing characters on the edges are joined onto any already in the frame, and
+    /// connected to lines that meet them (see `Frame::set_connected`), so boxes can share walls
+    /// and corridors join onto rooms. If `rect` is only one cell wide or high, its opposite edges
+    /// are drawn in the same cells, and joined the same way.
+    fn draw(frame: &mut Frame, rect: Rect) {
+        let edges = [
+            Self::TOP_LEFT.to_char(),
+            Self::TOP.to_char(),
+            Self::TOP_RIGHT.to_char(),
+            Self::LEFT.to_char(),
+            Self::RIGHT.to_char(),
+            Self::BOTTOM_LEFT.to_char(),
+            Self::BOTTOM.to_char(),
+            Self::BOTTOM_RIGHT.to_char(),
+        ];
+        let middle = if Self::FILL_MIDDLE {
+            Some(Self::MIDDLE.to_char())
+        } else {
+            None
+        };
+        draw_patch(frame, rect, edges, middle);
+    }
+}
+
+/// Draw a nine-patch, like `NinePatch::draw`. `edges` are the top-left, top, top-right, left,
+/// right, bottom-left, bottom and bottom-right tiles. The middle is only drawn if there is one.
+pub(crate) fn draw_patch(frame: &mut Frame, rect: Rect, edges: [Char; 8], middle: Option<Char>) {
+    let [top_left, top, top_right, left, right_edge, bottom_left, bottom_edge, bottom_right] =
+        edges;
+    if rect.is_empty() {
+        return;
+    }
+    // in frame coordinates rather than a view, so the edges can connect to lines outside `rect`
+    let Point2 {
+        x: left_x,
+        y: top_y,
+    } = rect.top_left;
+    let (right_x, bottom_y) = (rect.bottom_right.x - 1, rect.bottom_right.y - 1);
+
+    // before the edges, so they don't connect to lines that are about to be covered up
+    if let Some(middle) = middle {
+        frame.fill_rect(rect.deflate(1), middle);
+    }
+    for x in left_x + 1..right_x {
+        frame.set_connected((x, top_y), top);
+        frame.set_connected((x, bottom_y), bottom_edge);
+    }
+    for y in top_y + 1..bottom_y {
+        frame.set_connected((left_x, y), left);
+        frame.set_connected((right_x, y), right_edge);
+    }
+    frame.set_connected((left_x, top_y), top_left);
+    frame.set_connected((right_x, top_y), top_right);
+    frame.set_connected((left_x, bottom_y), bottom_left);
+    frame.set_connected((right_x, bottom_y), bottom_right);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing::screen, Color};
+
+    fn rect(left: usize, top: usize, right: usize, bottom: usize) -> Rect {
+        Rect::from_parts(left, top, right, bottom)
+    }
+
+    #[test]
+    fn intersection() {
+        let a = rect(0, 0, 4, 3);
+        assert_eq!(a.intersection(rect(2, 1, 6, 5)), rect(2, 1, 4, 3));
+        assert_eq!(a.intersection(rect(1, 1, 2, 2)), rect(1, 1, 2, 2));
+        assert_eq!(a.intersection(a), a);
+        // rects that only touch, or are far apart, don't overlap
+        for &other in &[rect(4, 0, 6, 3), rect(0, 3, 4, 5), rect(10, 10, 12, 12)] {
+            let overlap = a.intersection(other);
+            assert!(overlap.is_empty(), "{:?}", other);
+            assert_eq!(overlap.cells().count(), 0);
+        }
+        let overlap = rect(10, 10, 12, 12).intersection(a);
+        assert!(overlap.is_empty());
+        assert_eq!(overlap.cells().count(), 0);
+    }
+
+    #[test]
+    fn union() {
+        let a = rect(0, 0, 2, 2);
+        assert_eq!(a.union(rect(5, 1, 6, 4)), rect(0, 0, 6, 4));
+        assert_eq!(a.union(rect(1, 1, 2, 2)), a);
+        // empty rects don't stretch the union out to them
+        assert_eq!(a.union(rect(9, 9, 9, 12)), a);
+        assert_eq!(rect(9, 9, 9, 9).union(a), a);
+        assert!(rect(3, 3, 3, 3).union(rect(5, 5, 6, 5)).is_empty());
+    }
+
+    #[test]
+    fn inflate_and_deflate() {
+        let a = rect(2, 1, 8, 5);
+        assert_eq!(a.inflate(1), rect(1, 0, 9, 6));
+        assert_eq!(a.inflate(3), rect(0, 0, 11, 8));
+        assert_eq!(a.deflate(1), rect(3, 2, 7, 4));
+        assert_eq!(a.deflate(2), rect(4, 3, 6, 3));
+        // past zero size, it shrinks to nothing in the middle
+        for by in 3..6 {
+            let deflated = a.deflate(by);
+            assert!(deflated.is_empty(), "{}", by);
+            assert_eq!(deflated, rect(5, 3, 5, 3), "{}", by);
+        }
+        assert_eq!(rect(3, 3, 3, 3).deflate(1), rect(3, 3, 3, 3));
+    }
+
+    #[test]
+    fn splitting() {
+        let a = rect(1, 2, 11, 6);
+        assert_eq!(
+            a.split_horizontally(1),
+            (rect(1, 2, 11, 3), rect(1, 3, 11, 6))
+        );
+        assert_eq!(a.split_horizontally(9), (a, rect(1, 6, 11, 6)));
+        assert_eq!(a.split_vertically(4), (rect(1, 2, 5, 6), rect(5, 2, 11, 6)));
+        assert_eq!(a.split_vertically(20), (a, rect(11, 2, 11, 6)));
+
+        let tests = [
+            (0.5, 5),
+            (0.26, 3),
+            (0.24, 2),
+            (0.0, 0),
+            (1.0, 10),
+            (-1.0, 0),
+            (2.0, 10),
+        ];
+        for &(ratio, width) in &tests {
+            let (left, right) = a.split_vertically_ratio(ratio);
+            assert_eq!(left.width(), width, "{}", ratio);
+            assert_eq!(left.width() + right.width(), 10);
+            assert_eq!(left.union(right), a);
+        }
+        assert_eq!(a.split_horizontally_ratio(0.5).0, rect(1, 2, 11, 4));
+        assert_eq!(a.split_horizontally_ratio(7.0).0, a);
+    }
+
+    #[test]
+    fn cells() {
+        let cells: Vec<_> = rect(1, 1, 3, 3).cells().map(|p| (p.x, p.y)).collect();
+        assert_eq!(cells, [(1, 1), (2, 1), (1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn edge_cells() {
+        let edge =
+            |r: Rect| -> Vec<(usize, usize)> { r.edge_cells().map(|p| (p.x, p.y)).collect() };
+        assert_eq!(
+            edge(rect(0, 0, 3, 3)),
+            [
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (1, 2),
+                (0, 2),
+                (0, 1)
+            ]
+        );
+        assert_eq!(edge(rect(0, 0, 2, 2)), [(0, 0), (1, 0), (1, 1), (0, 1)]);
+        // thin rects don't count any cell twice
+        assert_eq!(edge(rect(2, 1, 3, 4)), [(2, 1), (2, 2), (2, 3)]);
+        assert_eq!(edge(rect(1, 2, 4, 3)), [(1, 2), (2, 2), (3, 2)]);
+        assert_eq!(edge(rect(1, 1, 2, 2)), [(1, 1)]);
+        assert_eq!(edge(rect(1, 1, 1, 4)), []);
+        assert_eq!(edge(rect(1, 1, 4, 1)), []);
+        // and every cell of a bigger rect is on the edge or in the deflated middle
+        let a = rect(2, 3, 9, 7);
+        let mut cells: Vec<_> = a.edge_cells().chain(a.deflate(1).cells()).collect();
+        cells.sort_by_key(|p| (p.y, p.x));
+        assert_eq!(cells, a.cells().collect::<Vec<_>>());
+    }
+
+    const fn tile(ch: char) -> Char {
+        Char {
+            ch,
+            fg: Color::WHITE,
+            bg: Color::TRANSPARENT,
+        }
+    }
+
+    struct Room;
+
+    impl NinePatch for Room {
+        type TileSet = Char;
+        const TOP_LEFT: Char = tile('┌');
+        const TOP: Char = tile('─');
+        const TOP_RIGHT: Char = tile('┐');
+        const LEFT: Char = tile('│');
+        const MIDDLE: Char = tile('.');
+        const RIGHT: Char = tile('│');
+        const BOTTOM_LEFT: Char = tile('└');
+        const BOTTOM: Char = tile('─');
+        const BOTTOM_RIGHT: Char = tile('┘');
+    }
+
+    fn rooms(width: usize, height: usize, rects: &[Rect]) -> String {
+        let mut frame = Frame::new(Point2 {
+            x: width,
+            y: height,
+        });
+        for &rect in rects {
+            Room::draw(&mut frame, rect);
+        }
+        screen(&frame)
+    }
+
+    #[test]
+    fn nine_patches() {
+        assert_eq!(rooms(4, 3, &[rect(0, 0, 4, 3)]), "┌──┐\n│..│\n└──┘");
+        assert_eq!(rooms(3, 3, &[rect(1, 1, 1, 1)]), "   \n   \n   ");
+        assert_eq!(rooms(3, 3, &[rect(1, 1, 1, 3)]), "   \n   \n   ");
+        // opposite edges land on the same cells, and are joined
+        assert_eq!(rooms(3, 3, &[rect(1, 1, 2, 2)]), "   \n ┼ \n   ");
+        assert_eq!(rooms(3, 3, &[rect(1, 0, 2, 3)]), " ┬ \n │ \n ┴ ");
+        assert_eq!(rooms(3, 3, &[rect(0, 1, 3, 2)]), "   \n├─┤\n   ");
+        assert_eq!(rooms(4, 2, &[rect(0, 0, 2, 2)]), "┌┐  \n└┘  ");
+        // clipped to the frame
+        assert_eq!(rooms(3, 2, &[rect(1, 1, 5, 4)]), "   \n ┌─");
+    }
+
+    #[test]
+    fn nine_patches_share_walls() {
+        assert_eq!(
+            rooms(7, 3, &[rect(0, 0, 4, 3), rect(3, 0, 7, 3)]),
+            "┌──┬──┐\n│..│..│\n└──┴──┘"
+        );
+        assert_eq!(
+            rooms(4, 5, &[rect(0, 0, 4, 3), rect(0, 2, 4, 5)]),
+            "┌──┐\n│..│\n├──┤\n│..│\n└──┘"
+        );
+        // a room drawn over the corner of another
+        assert_eq!(
+            rooms(5, 4, &[rect(0, 0, 3, 3), rect(2, 2, 5, 4)]),
+            "┌─┐  \n│.│  \n└─┼─┐\n  └─┘"
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! Stacks of frames drawn on top of each other.
+//!
+//! Each layer is a whole `Frame`, starting out transparent. When layers are composited, a cell's
+//! background is alpha-blended over the cells below it, so translucent overlays tint what's
+//! underneath. A character with a visible foreground (after the layer's opacity is applied)
+//! replaces any character below it, taking on a blend of the two colors if it is translucent, and
+//! blank cells (spaces with a transparent background) let the layers below show through.
+use crate::{text::WIDE_CHAR_TAIL, Char, Frame};
+use mint::Point2;
+use std::collections::BTreeMap;
+
+/// A frame with an opacity, as part of `Layers`.
+#[derive(Debug, Clone)]
+pub struct Layer {
+    pub frame: Frame,
+    /// How opaque the whole layer is, from 0 (invisible) to 1. Defaults to 1.
+    pub opacity: f32,
+}
+
+impl Layer {
+    fn new(size: Point2<usize>) -> Self {
+        Layer {
+            frame: Frame::new(size),
+            opacity: 1.0,
+        }
+    }
+}
+
+/// A stack of layers, each identified by its z-order. Layers with a higher z are drawn on top.
+///
+/// ```ignore
+/// layers.layer(0).frame.print(pos, "You see an orc", fg, bg);
+/// layers.layer(10).opacity = 0.5;
+/// layers.composite(frame);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Layers {
+    size: Point2<usize>,
+    layers: BTreeMap<i32, Layer>,
+}
+
+impl Layers {
+    /// An empty stack, for frames of the given size.
+    pub fn new(size: Point2<usize>) -> Self {
+        Layers {
+            size,
+            layers: BTreeMap::new(),
+        }
+    }
+
+    /// The size of every layer, in cells.
+    pub fn size(&self) -> Point2<usize> {
+        self.size
+    }
+
+    /// The layer at `z`, creating a transparent one if there isn't one yet.
+    pub fn layer(&mut self, z: i32) -> &mut Layer {
+        let size = self.size;
+        self.layers.entry(z).or_insert_with(|| Layer::new(size))
+    }
+
+    /// The layer at `z`, if there is one.
+    pub fn get(&self, z: i32) -> Option<&Layer> {
+        self.layers.get(&z)
+    }
+
+    /// Take the layer at `z` out of the stack.
+    pub fn remove(&mut self, z: i32) -> Option<Layer> {
+        self.layers.remove(&z)
+    }
+
+    /// Clear every layer back to transparent. The layers and their opacities are kept.
+    pub fn clear(&mut self) {
+        for layer in self.layers.values_mut() {
+            layer.frame.clear();
+        }
+    }
+
+    /// Resize every layer, like `Frame::resize`.
+    pub fn resize(&mut self, size: Point2<usize>) {
+        self.size = size;
+        for layer in self.layers.values_mut() {
+            layer.frame.resize(size);
+        }
+    }
+
+    /// The z-orders of the layers, from the bottom up.
+    pub fn z_orders(&self) -> impl Iterator<Item = i32> + '_ {
+        self.layers.keys().cloned()
+    }
+
+    /// Draw the layers, from the bottom up, over the contents of `frame`.
+    pub fn composite(&self, frame: &mut Frame) {
+        for layer in self.layers.values() {
+            frame.composite(&layer.frame, layer.opacity);
+        }
+    }
+}
+
+impl Frame {
+    /// Draw `layer` over this frame, with its colors' alpha multiplied by `opacity`.
+    ///
+    /// Only the cells inside both frames are touched.
+    pub fn composite(&mut self, layer: &Frame, opacity: f32) {
+        let size = Point2 {
+            x: self.size.x.min(layer.size.x),
+            y: self.size.y.min(layer.size.y),
+        };
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let bottom = self[(x, y)];
+                let cell = composite_char(bottom, layer[(x, y)], opacity);
+                if bottom.ch == WIDE_CHAR_TAIL && cell.ch != WIDE_CHAR_TAIL && x > 0 {
+                    // don't leave half of a wide character below sticking out
+                    self[(x - 1, y)].ch = ' ';
+                }
+                self[(x, y)] = cell;
+            }
+        }
+    }
+}
+
+/// Whether the cell's character can be seen, and so would hide the one below it.
+fn has_glyph(ch: &Char) -> bool {
+    ch.ch != ' ' && !ch.fg.is_transparent()
+}
+
+/// The cell you get by drawing `top`, with its colors faded by `opacity`, over `bottom`.
+fn composite_char(bottom: Char, top: Char, opacity: f32) -> Char {
+    let top = Char {
+        fg: top.fg.faded(opacity),
+        bg: top.bg.faded(opacity),
+        ..top
+    };
+    let bg = bottom.bg.over(top.bg);
+    // the top background partly hides the character below
+    let bottom_fg = if bottom.fg.is_transparent() {
+        bottom.fg
+    } else {
+        bottom.fg.over(top.bg)
+    };
+    match (has_glyph(&top), has_glyph(&bottom)) {
+        // only one character fits, so a translucent one takes on some of the color below
+        (true, true) => Char {
+            ch: top.ch,
+            fg: bottom_fg.over(top.fg),
+            bg,
+        },
+        (true, false) => Char { bg, ..top },
+        (false, _) => Char {
+            ch: bottom.ch,
+            fg: bottom_fg,
+            bg,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    const BOTTOM: Char = Char {
+        ch: '#',
+        fg: Color::WHITE,
+        bg: Color::BLACK,
+    };
+    const TOP: Char = Char {
+        ch: '@',
+        fg: Color::RED,
+        bg: Color::BLUE,
+    };
+
+    fn composite(bottom: Char, top: Char, opacity: f32) -> Char {
+        let mut frame = Frame::new(Point2 { x: 1, y: 1 });
+        frame[(0, 0)] = bottom;
+        let mut layer = Frame::new(Point2 { x: 1, y: 1 });
+        layer[(0, 0)] = top;
+        frame.composite(&layer, opacity);
+        frame[(0, 0)]
+    }
+
+    #[test]
+    fn opaque_layers_replace_cells() {
+        assert_eq!(composite(BOTTOM, TOP, 1.0), TOP);
+    }
+
+    #[test]
+    fn invisible_layers_change_nothing() {
+        assert_eq!(composite(BOTTOM, TOP, 0.0), BOTTOM);
+        let faded_out = Char {
+            fg: Color::RED.with_alpha(0),
+            ..TOP
+        };
+        assert_eq!(composite(BOTTOM, faded_out, 1.0).ch, '#');
+    }
+
+    #[test]
+    fn half_opaque_layers_blend() {
+        let cell = composite(BOTTOM, TOP, 0.5);
+        assert_eq!(cell.ch, '@');
+        assert_eq!(cell.bg, Color::BLACK.over(Color::BLUE.faded(0.5)));
+        // the bottom character is tinted by the top background, then blended with the top one
+        let bottom_fg = Color::WHITE.over(Color::BLUE.faded(0.5));
+        assert_eq!(cell.fg, bottom_fg.over(Color::RED.faded(0.5)));
+        assert_eq!(cell.fg.a, 255);
+        assert!(cell.fg.r > cell.fg.g && cell.fg.g > 0);
+    }
+
+    #[test]
+    fn blank_cells_show_through() {
+        let blank = Char::default();
+        assert_eq!(composite(BOTTOM, blank, 1.0), BOTTOM);
+        let tint = Char {
+            bg: Color::BLUE.with_alpha(128),
+            ..blank
+        };
+        let cell = composite(BOTTOM, tint, 1.0);
+        assert_eq!(cell.ch, '#');
+        assert_eq!(cell.bg, Color::BLACK.over(tint.bg));
+        assert_eq!(cell.fg, Color::WHITE.over(tint.bg));
+    }
+
+    #[test]
+    fn layers_composite_bottom_up() {
+        let mut layers = Layers::new(Point2 { x: 1, y: 1 });
+        layers.layer(5).frame[(0, 0)] = TOP;
+        layers.layer(-1).frame[(0, 0)] = BOTTOM;
+        let mut frame = Frame::new(Point2 { x: 1, y: 1 });
+        layers.composite(&mut frame);
+        assert_eq!(frame[(0, 0)], TOP);
+        layers.layer(5).opacity = 0.0;
+        let mut frame = Frame::new(Point2 { x: 1, y: 1 });
+        layers.composite(&mut frame);
+        assert_eq!(frame[(0, 0)], BOTTOM);
+    }
+
+    #[test]
+    fn covering_half_a_wide_character_removes_it() {
+        let mut frame = Frame::new(Point2 { x: 3, y: 1 });
+        frame.print(Point2 { x: 0, y: 0 }, "漢", Color::WHITE, Color::BLACK);
+        let mut layer = Frame::new(Point2 { x: 3, y: 1 });
+        layer[(1, 0)] = TOP;
+        frame.composite(&layer, 0.0);
+        assert_eq!(frame[(0, 0)].ch, '漢');
+        frame.composite(&layer, 1.0);
+        assert_eq!(frame[(0, 0)].ch, ' ');
+        assert_eq!(frame[(1, 0)], TOP);
+    }
+}
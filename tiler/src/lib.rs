@@ -1,356 +1,581 @@
 use mint::Point2;
-use raw_window_handle::HasRawWindowHandle;
-use std::ops::{Index, IndexMut};
 use std::{
-    f32,
+    collections::HashSet,
     time::{Duration, Instant},
 };
-use wgpu_glyph::{GlyphBrushBuilder, Scale, Section};
 pub use winit;
-pub use winit::event::VirtualKeyCode as KeyCode;
-use winit::{
-    event::{DeviceEvent, ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
-    platform::unix::WindowBuilderExtUnix,
-    window::WindowBuilder,
-};
+pub use winit::event::{ModifiersState as Modifiers, MouseButton, VirtualKeyCode as KeyCode};
+
+mod backend;
+mod border;
+pub mod box_drawing;
+mod config;
+mod error;
+mod frame;
+pub mod geom;
+mod layer;
+pub mod markup;
+mod raster;
+mod shape;
+pub mod snapshot;
+pub mod testing;
+pub mod text;
+mod timing;
+mod view;
+pub mod widget;
 
+pub use crate::{
+    backend::{headless::Headless, term::ColorMode, Backend, GridMode},
+    border::{Border, BorderStyle},
+    config::{Config, WindowSize},
+    error::Error,
+    frame::{CellPos, Char, Frame},
+    geom::{NinePatch, Rect},
+    layer::{Layer, Layers},
+    raster::{Image, Rasterizer},
+    text::Align,
+    timing::UpdateMode,
+    view::FrameView,
+};
+pub use tiler_color::{Color, ParseColorError};
 pub use tiler_derive::TileSet;
 
 pub trait TileSet {
     fn to_char(&self) -> Char;
 }
 
+impl TileSet for Char {
+    fn to_char(&self) -> Char {
+        *self
+    }
+}
+
 const FULL_BLOCK: char = '█';
 const FONT: &'static [u8] = include_bytes!("../source_code_pro.ttf");
 
+#[allow(unused_variables)]
 pub trait App {
     const NAME: &'static str;
+    /// The size of the grid, in cells. With `GridMode::FitWindow` this is only the starting size.
     const SIZE: Point2<usize>;
+    /// Whether the grid is a fixed size, or follows the size of the window.
+    const GRID_MODE: GridMode = GridMode::Fixed;
+    /// How often `update` is called to begin with. Can be changed with
+    /// `Context::set_update_mode`.
+    const UPDATE_MODE: UpdateMode = UpdateMode::Fixed(60);
 
     /// Update state and draw to the supplied frame.
-    fn update(&mut self, frame: &mut Frame);
-    fn key_down_event(&mut self, mut ctx: Context<'_>, keycode: KeyCode) {
+    ///
+    /// The time since the last update is available from `ctx.delta()`.
+    fn update(&mut self, ctx: Context<'_>, frame: &mut Frame);
+    /// Called when the grid changes size, before the next update. Only happens with
+    /// `GridMode::FitWindow`.
+    ///
+    /// This can be called straight after `on_start`, if the window or terminal isn't `SIZE`.
+    fn resized(&mut self, ctx: Context<'_>, size: Point2<usize>) {}
+    /// Called once, before the first update.
+    fn on_start(&mut self, ctx: Context<'_>) {}
+    /// Called when the user asks to close the window (or presses ctrl-c in the terminal). Return
+    /// `false` to keep running, e.g. to ask "are you sure?" first.
+    fn on_close_requested(&mut self, ctx: Context<'_>) -> bool {
+        true
+    }
+    /// Called once when the app stops, however that happens, so it can save its state.
+    ///
+    /// The exit code is available from `ctx.exit_code()`.
+    fn on_exit(&mut self, ctx: Context<'_>) {}
+    /// Called when the window gains or loses keyboard focus, e.g. to pause the game.
+    fn focus_changed(&mut self, ctx: Context<'_>, focused: bool) {}
+    /// Called when a key is pressed, and again for each key repeat while it is held.
+    fn key_down_event(&mut self, mut ctx: Context<'_>, keycode: KeyCode, modifiers: Modifiers) {
         match keycode {
             KeyCode::Escape => ctx.exit(),
             _ => (),
         };
     }
-    //#[allow(unused_variables)]
-    fn key_up_event(&mut self, keycode: KeyCode) {}
+    /// Called when a key is released.
+    fn key_up_event(&mut self, ctx: Context<'_>, keycode: KeyCode, modifiers: Modifiers) {}
+    /// Called with each character of text the user types.
+    ///
+    /// Unlike key events this follows the keyboard layout and shift state, and includes
+    /// characters composed with an input method. Control characters (backspace, return, ...)
+    /// are not sent here; use the key events for those.
+    fn text_input(&mut self, ctx: Context<'_>, ch: char) {}
+    /// Called when the mouse moves onto a different cell of the grid.
+    fn mouse_moved(&mut self, ctx: Context<'_>, cell: Point2<usize>) {}
+    /// Called when the mouse leaves the grid.
+    fn mouse_left(&mut self, ctx: Context<'_>) {}
+    /// Called when a mouse button is pressed over the grid.
+    fn mouse_down(&mut self, ctx: Context<'_>, button: MouseButton, cell: Point2<usize>) {}
+    /// Called when a mouse button that was pressed over the grid is released.
+    ///
+    /// If the mouse has left the grid, `cell` is the last cell it was over.
+    fn mouse_up(&mut self, ctx: Context<'_>, button: MouseButton, cell: Point2<usize>) {}
+    /// Called when the mouse wheel is scrolled over the grid.
+    ///
+    /// `delta` is in lines, with positive `y` meaning away from the user (up).
+    fn mouse_wheel(&mut self, ctx: Context<'_>, delta: Point2<f32>, cell: Point2<usize>) {}
 }
 
+/// The app plus everything the runtime keeps alongside it, independent of the backend.
 struct AppContainer<A>
 where
     A: App,
 {
     app: A,
     frame_buf: Frame,
+    state: ContextState,
 }
 
-pub struct Context<'a> {
-    control_flow: &'a mut ControlFlow,
-}
+impl<A> AppContainer<A>
+where
+    A: App,
+{
+    fn new(app: A) -> Self {
+        let state = ContextState {
+            update_mode: A::UPDATE_MODE,
+            grid_size: A::SIZE,
+            ..ContextState::default()
+        };
+        AppContainer {
+            app,
+            frame_buf: Frame::new(A::SIZE),
+            state,
+        }
+    }
 
-impl<'a> Context<'a> {
-    pub fn exit(&mut self) {
-        *self.control_flow = ControlFlow::Exit;
+    /// Clear the frame and let the app draw a new one, `delta` after the last update.
+    fn update(&mut self, delta: Duration) {
+        self.state.delta = delta;
+        self.state.elapsed += delta;
+        self.state.update_requested = false;
+        self.frame_buf.clear();
+        let ctx = Context {
+            state: &mut self.state,
+        };
+        self.app.update(ctx, &mut self.frame_buf);
+        self.state.tick += 1;
     }
-}
 
-pub fn run<A>(mut app: A) -> Result<(), Box<dyn std::error::Error + 'static>>
-where
-    A: App + 'static,
-{
-    let mut app_ctr = AppContainer {
-        app,
-        frame_buf: Frame::new(A::SIZE),
-    };
-    let instance = wgpu::Instance::new();
-    let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions {
-        power_preference: wgpu::PowerPreference::HighPerformance,
-    });
-    let mut device = adapter.request_device(&wgpu::DeviceDescriptor {
-        extensions: wgpu::Extensions {
-            anisotropic_filtering: false,
-        },
-        limits: wgpu::Limits { max_bind_groups: 1 },
-    });
-    let event_loop = EventLoop::new();
-    let window = WindowBuilder::new()
-        //.with_resizable(false)
-        .with_class("floating".into(), "floating".into())
-        .build(&event_loop)?;
-    let surface = instance.create_surface(window.raw_window_handle());
-
-    let render_format = wgpu::TextureFormat::Bgra8UnormSrgb;
-    let mut size = window.inner_size().to_physical(window.hidpi_factor());
-
-    let mut swap_chain = device.create_swap_chain(
-        &surface,
-        &wgpu::SwapChainDescriptor {
-            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
-            format: render_format,
-            width: size.width.round() as u32,
-            height: size.height.round() as u32,
-            present_mode: wgpu::PresentMode::Vsync,
-        },
-    );
-
-    let mut glyph_brush =
-        GlyphBrushBuilder::using_font_bytes(FONT).build(&mut device, render_format);
-
-    //let mut last_resize_time: Option<Instant> = None;
-    let mut tmp_str = String::from(" ");
-    event_loop.run(move |event, _, control_flow| {
-        match event {
-            Event::EventsCleared => {
-                // update state
-                app_ctr.frame_buf.clear();
-                app_ctr.app.update(&mut app_ctr.frame_buf);
-                window.request_redraw();
-            }
-            Event::WindowEvent {
-                event: WindowEvent::RedrawRequested,
-                ..
-            } => {
-                log::trace!("draw");
-                // draw
-                let mut encoder =
-                    device.create_command_encoder(&wgpu::CommandEncoderDescriptor { todo: 0 });
-
-                let frame = swap_chain.get_next_texture();
-                {
-                    let _ = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                        color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
-                            attachment: &frame.view,
-                            resolve_target: None,
-                            load_op: wgpu::LoadOp::Clear,
-                            store_op: wgpu::StoreOp::Store,
-                            clear_color: wgpu::Color {
-                                r: 0.0,
-                                g: 0.0,
-                                b: 0.0,
-                                a: 1.0,
-                            },
-                        }],
-                        depth_stencil_attachment: None,
-                    });
-                }
-                let mut draw_char = |Point2 { x, y }: Point2<usize>, ch: char, color: [f32; 4]| {
-                    tmp_str.clear();
-                    tmp_str.push(ch);
-                    glyph_brush.queue(Section {
-                        text: &tmp_str,
-                        screen_position: (
-                            x as f32 * size.width as f32 / A::SIZE.x as f32,
-                            y as f32 * size.height as f32 / A::SIZE.y as f32,
-                        ),
-                        color,
-                        scale: Scale {
-                            x: size.width as f32 / (A::SIZE.x as f32 / 2.0),
-                            y: size.height as f32 / A::SIZE.y as f32,
-                        },
-                        bounds: (size.width as f32, size.height as f32),
-                        ..Section::default()
-                    });
-                };
-                let frame_size = app_ctr.frame_buf.size;
-                for x in 0..frame_size.x {
-                    for y in 0..frame_size.y {
-                        let idx = Point2 { x, y };
-                        let ch = app_ctr.frame_buf.get(idx);
-                        // background
-                        if ch.bg[3] > f32::EPSILON {
-                            draw_char(idx, FULL_BLOCK, ch.bg);
-                        }
-                        // foreground
-                        if ch.fg[3] > f32::EPSILON {
-                            draw_char(idx, ch.ch, ch.fg);
-                        }
-                    }
-                }
-                if let Err(e) = glyph_brush.draw_queued(
-                    &mut device,
-                    &mut encoder,
-                    &frame.view,
-                    size.width.round() as u32,
-                    size.height.round() as u32,
-                ) {
-                    println!("Error drawing queued glyphs: {}", e);
-                    *control_flow = ControlFlow::Exit;
-                }
-                device.get_queue().submit(&[encoder.finish()]);
-            }
-            Event::WindowEvent {
-                event: WindowEvent::CloseRequested,
-                ..
-            } => {
-                println!("The close button was pressed; stopping");
-                *control_flow = ControlFlow::Exit
+    /// Reallocate the frame with a new size, and tell the app.
+    fn resize(&mut self, size: Point2<usize>) {
+        if size == self.frame_buf.size() {
+            return;
+        }
+        self.frame_buf.resize(size);
+        self.state.grid_size = size;
+        self.state.update_requested = true;
+        let ctx = Context {
+            state: &mut self.state,
+        };
+        self.app.resized(ctx, size);
+        // the mouse may now be off the grid
+        if let Some(cell) = self.state.mouse_cell {
+            if cell.x >= size.x || cell.y >= size.y {
+                self.mouse_moved(None);
             }
-            Event::DeviceEvent {
-                event:
-                    DeviceEvent::Key(KeyboardInput {
-                        virtual_keycode,
-                        state,
-                        ..
-                    }),
-                ..
-            } => {
-                if let Some(keycode) = virtual_keycode {
-                    let mut ctx = Context { control_flow };
-                    match state {
-                        ElementState::Pressed => app_ctr.app.key_down_event(ctx, keycode),
-                        _ => (),
+        }
+    }
+
+    /// The delta to use for each update when they are triggered by hand rather than by the clock.
+    fn manual_tick(&self) -> Duration {
+        self.state
+            .update_mode
+            .tick_duration()
+            .unwrap_or(timing::DEFAULT_TICK)
+    }
+
+    /// Run any updates that are due at `now`. Returns whether the frame was redrawn.
+    fn run_due_updates(&mut self, now: Instant) -> bool {
+        let mode = self.state.update_mode;
+        let updated = match mode.tick_duration() {
+            Some(step) => {
+                let mut next = self.state.next_tick.unwrap_or(now);
+                let mut ticks = 0;
+                while next <= now && self.state.update_mode == mode {
+                    if ticks == timing::MAX_CATCH_UP_TICKS {
+                        next = now + step;
+                        break;
                     }
+                    self.update(step);
+                    next += step;
+                    ticks += 1;
                 }
+                // If the app changed the mode, start the new one from scratch.
+                self.state.next_tick = if self.state.update_mode == mode {
+                    Some(next)
+                } else {
+                    None
+                };
+                ticks > 0
             }
-            Event::WindowEvent {
-                event: WindowEvent::Resized(new_size),
-                ..
-            } => {
-                // let's debounce this every 100ms.
-                // TODO this isn't debouncing, need to schedule a resize
-                /*
-                if let Some(time) = last_resize_time {
-                    if Instant::now().duration_since(time) < Duration::from_millis(100) {
-                        return;
-                    }
+            None => {
+                if self.state.update_requested {
+                    let delta = match self.state.last_update {
+                        Some(last) => now.duration_since(last),
+                        None => Duration::from_secs(0),
+                    };
+                    self.update(delta);
+                    true
+                } else {
+                    false
                 }
-                last_resize_time = Some(Instant::now());
-                */
-                size = new_size.to_physical(window.hidpi_factor());
-                swap_chain = device.create_swap_chain(
-                    &surface,
-                    &wgpu::SwapChainDescriptor {
-                        usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
-                        format: render_format,
-                        width: size.width.round() as u32,
-                        height: size.height.round() as u32,
-                        present_mode: wgpu::PresentMode::Vsync,
-                    },
-                );
-                window.request_redraw();
             }
-            _ => *control_flow = ControlFlow::Poll,
+        };
+        if updated {
+            self.state.last_update = Some(now);
         }
-    });
-}
+        updated
+    }
 
-#[derive(Debug, Clone)]
-pub struct Frame {
-    buf: Vec<Char>,
-    size: Point2<usize>,
-}
+    /// When `run_due_updates` next needs calling, or `None` if we should wait for input.
+    fn next_update(&self, now: Instant) -> Option<Instant> {
+        match self.state.update_mode {
+            UpdateMode::Fixed(_) => Some(self.state.next_tick.unwrap_or(now)),
+            UpdateMode::WaitForInput if self.state.update_requested => Some(now),
+            UpdateMode::WaitForInput => None,
+        }
+    }
 
-#[derive(Debug, Copy, Clone)]
-pub struct Char {
-    /// Get the character for this tile.
-    pub ch: char,
-    /// The foreground color of the character
-    pub fg: [f32; 4],
-    /// The background color of the character
-    pub bg: [f32; 4],
-}
+    /// Record the key as held and tell the app.
+    ///
+    /// If the backend doesn't know the modifiers, pass `None` and they will be worked out from
+    /// the modifier keys that are held down.
+    fn key_down(&mut self, keycode: KeyCode, modifiers: Option<Modifiers>) {
+        self.state.update_requested = true;
+        self.state.keys_down.insert(keycode);
+        let modifiers = self.state.update_modifiers(modifiers);
+        let ctx = Context {
+            state: &mut self.state,
+        };
+        self.app.key_down_event(ctx, keycode, modifiers);
+    }
 
-impl Default for Char {
-    fn default() -> Self {
-        Self {
-            ch: ' ',
-            fg: [0.0; 4],
-            bg: [0.0; 4],
+    /// Record the key as released and tell the app.
+    fn key_up(&mut self, keycode: KeyCode, modifiers: Option<Modifiers>) {
+        self.state.update_requested = true;
+        self.state.keys_down.remove(&keycode);
+        let modifiers = self.state.update_modifiers(modifiers);
+        let ctx = Context {
+            state: &mut self.state,
+        };
+        self.app.key_up_event(ctx, keycode, modifiers);
+    }
+
+    /// Pass typed text to the app, skipping control characters.
+    fn text_input(&mut self, ch: char) {
+        if ch.is_control() {
+            return;
         }
+        self.state.update_requested = true;
+        let ctx = Context {
+            state: &mut self.state,
+        };
+        self.app.text_input(ctx, ch);
     }
-}
 
-impl Frame {
-    pub fn new(size: Point2<usize>) -> Self {
-        let area = size.x * size.y;
-        let mut buf = Vec::with_capacity(area);
-        for _ in 0..area {
-            buf.push(Default::default());
+    /// Move the mouse to `cell`, or off the grid if it is `None`.
+    fn mouse_moved(&mut self, cell: Option<Point2<usize>>) {
+        if cell == self.state.mouse_cell {
+            return;
+        }
+        self.state.mouse_cell = cell;
+        self.state.update_requested = true;
+        let ctx = Context {
+            state: &mut self.state,
+        };
+        match cell {
+            Some(cell) => self.app.mouse_moved(ctx, cell),
+            None => self.app.mouse_left(ctx),
+        }
+        if let Some(cell) = cell {
+            self.state.last_mouse_cell = cell;
         }
-        Self { buf, size }
     }
 
-    pub fn clear(&mut self) {
-        for el in self.buf.iter_mut() {
-            *el = Default::default();
+    /// Press a mouse button at the current mouse position. Ignored if the mouse is off the grid.
+    fn mouse_down(&mut self, button: MouseButton) {
+        if let Some(cell) = self.state.mouse_cell {
+            self.state.mouse_buttons.insert(button);
+            self.state.update_requested = true;
+            let ctx = Context {
+                state: &mut self.state,
+            };
+            self.app.mouse_down(ctx, button, cell);
         }
     }
 
-    pub fn from_fn(width: usize, height: usize, f: impl Fn(usize, usize) -> Char) -> Self {
-        let mut buf = Vec::with_capacity(width * height);
-        for x in 0..width {
-            for y in 0..height {
-                buf.push(f(width, height));
-            }
+    /// Release a mouse button. Ignored unless it was pressed over the grid.
+    fn mouse_up(&mut self, button: MouseButton) {
+        if self.state.mouse_buttons.remove(&button) {
+            self.state.update_requested = true;
+            let cell = self.state.last_mouse_cell;
+            let ctx = Context {
+                state: &mut self.state,
+            };
+            self.app.mouse_up(ctx, button, cell);
         }
-        Self {
-            buf,
-            size: Point2 {
-                x: width,
-                y: height,
-            },
+    }
+
+    /// Scroll the mouse wheel by `delta` lines. Ignored if the mouse is off the grid.
+    fn mouse_wheel(&mut self, delta: Point2<f32>) {
+        if let Some(cell) = self.state.mouse_cell {
+            self.state.update_requested = true;
+            let ctx = Context {
+                state: &mut self.state,
+            };
+            self.app.mouse_wheel(ctx, delta, cell);
         }
     }
 
-    /// Get an item from the grid by location.
-    ///
-    /// You can also use the implementation of `Index` like so: `frame[(1, 2)]`.
-    #[inline]
-    pub fn get(&self, Point2 { x, y }: Point2<usize>) -> &Char {
-        &self.buf[self.idx(x, y)]
+    /// Release every held key, e.g. because the window lost focus and we won't see the releases.
+    fn release_all_keys(&mut self) {
+        let held: Vec<_> = self.state.keys_down.iter().cloned().collect();
+        for keycode in held {
+            self.key_up(keycode, None);
+        }
     }
 
-    /// Get a mutable ref to and item from the grid by location.
-    ///
-    /// You can also use the implementation of `IndexMut` like so: `frame[(1, 2)] = 2`.
-    #[inline]
-    pub fn get_mut(&mut self, Point2 { x, y }: Point2<usize>) -> &mut Char {
-        // to keep the borrowchecker happy
-        let idx = self.idx(x, y);
-        &mut self.buf[idx]
+    /// Tell the app it is starting, if we haven't already.
+    fn start(&mut self) {
+        if self.state.started {
+            return;
+        }
+        self.state.started = true;
+        let ctx = Context {
+            state: &mut self.state,
+        };
+        self.app.on_start(ctx);
     }
 
-    fn idx(&self, x: usize, y: usize) -> usize {
-        x * self.size.y + y
+    /// The user asked to close the app. Exits with code 0 unless the app vetoes it.
+    fn close_requested(&mut self) {
+        let ctx = Context {
+            state: &mut self.state,
+        };
+        if self.app.on_close_requested(ctx) && self.state.exit_code.is_none() {
+            self.state.exit_code = Some(0);
+        }
     }
 
-    pub fn debug_print(&self) {
-        println!("Frame:");
-        for y in 0..self.size.y {
-            for x in 0..self.size.x {
-                print!("{}", self.get(Point2 { x, y }).ch);
-            }
-            println!()
+    fn focus_changed(&mut self, focused: bool) {
+        if focused == self.state.focused {
+            return;
+        }
+        if !focused {
+            // We won't get the key releases while unfocused, so don't leave keys stuck down.
+            self.release_all_keys();
+        }
+        self.state.focused = focused;
+        self.state.update_requested = true;
+        let ctx = Context {
+            state: &mut self.state,
+        };
+        self.app.focus_changed(ctx, focused);
+    }
+
+    /// Whether the app has asked to exit.
+    fn should_exit(&self) -> bool {
+        self.state.exit_code.is_some()
+    }
+
+    /// Tell the app it is stopping (once only), and return the exit code.
+    fn finish(&mut self) -> i32 {
+        if self.state.exit_code.is_none() {
+            self.state.exit_code = Some(0);
+        }
+        if !self.state.finished {
+            self.state.finished = true;
+            let ctx = Context {
+                state: &mut self.state,
+            };
+            self.app.on_exit(ctx);
         }
+        self.state.exit_code.unwrap_or(0)
     }
 }
 
-impl Index<(usize, usize)> for Frame {
-    type Output = Char;
-    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
-        &self[Point2 { x, y }]
+/// State shared between the runtime and the app, that the app accesses through `Context`.
+#[derive(Debug)]
+struct ContextState {
+    /// Set once the app should stop.
+    exit_code: Option<i32>,
+    started: bool,
+    finished: bool,
+    focused: bool,
+    keys_down: HashSet<KeyCode>,
+    modifiers: Modifiers,
+    mouse_cell: Option<Point2<usize>>,
+    last_mouse_cell: Point2<usize>,
+    mouse_buttons: HashSet<MouseButton>,
+    grid_size: Point2<usize>,
+    fullscreen: bool,
+    update_mode: UpdateMode,
+    /// Set by input, or by the app, to ask for an update in `WaitForInput` mode.
+    update_requested: bool,
+    delta: Duration,
+    elapsed: Duration,
+    tick: u64,
+    /// When the last update happened in real time, if they are driven by the clock.
+    last_update: Option<Instant>,
+    /// When the next fixed tick is due.
+    next_tick: Option<Instant>,
+}
+
+impl Default for ContextState {
+    fn default() -> Self {
+        ContextState {
+            exit_code: None,
+            started: false,
+            finished: false,
+            focused: true,
+            keys_down: HashSet::new(),
+            modifiers: Modifiers::default(),
+            mouse_cell: None,
+            last_mouse_cell: Point2 { x: 0, y: 0 },
+            mouse_buttons: HashSet::new(),
+            grid_size: Point2 { x: 0, y: 0 },
+            fullscreen: false,
+            update_mode: UpdateMode::default(),
+            update_requested: true,
+            delta: Duration::from_secs(0),
+            elapsed: Duration::from_secs(0),
+            tick: 0,
+            last_update: None,
+            next_tick: None,
+        }
     }
 }
 
-impl IndexMut<(usize, usize)> for Frame {
-    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
-        &mut self[Point2 { x, y }]
+impl ContextState {
+    /// Store the modifiers reported by the backend, or if there aren't any work them out from the
+    /// keys held down.
+    fn update_modifiers(&mut self, modifiers: Option<Modifiers>) -> Modifiers {
+        self.modifiers = modifiers.unwrap_or_else(|| {
+            let held =
+                |left, right| self.keys_down.contains(&left) || self.keys_down.contains(&right);
+            Modifiers {
+                shift: held(KeyCode::LShift, KeyCode::RShift),
+                ctrl: held(KeyCode::LControl, KeyCode::RControl),
+                alt: held(KeyCode::LAlt, KeyCode::RAlt),
+                logo: held(KeyCode::LWin, KeyCode::RWin),
+            }
+        });
+        self.modifiers
     }
 }
 
-impl Index<Point2<usize>> for Frame {
-    type Output = Char;
-    fn index(&self, pos: Point2<usize>) -> &Self::Output {
-        self.get(pos)
+pub struct Context<'a> {
+    state: &'a mut ContextState,
+}
+
+impl<'a> Context<'a> {
+    /// Stop the app after the current callback, with exit code 0.
+    pub fn exit(&mut self) {
+        self.exit_with(0);
+    }
+
+    /// Stop the app after the current callback, making `run` return `code`.
+    pub fn exit_with(&mut self, code: i32) {
+        self.state.exit_code = Some(code);
+    }
+
+    /// The code the app will exit with, if it is exiting.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.state.exit_code
+    }
+
+    /// Whether the window has keyboard focus.
+    pub fn is_focused(&self) -> bool {
+        self.state.focused
+    }
+
+    /// Whether the key is currently held down.
+    pub fn is_key_down(&self, keycode: KeyCode) -> bool {
+        self.state.keys_down.contains(&keycode)
+    }
+
+    /// All the keys that are currently held down, in no particular order.
+    pub fn keys_down(&self) -> impl Iterator<Item = KeyCode> + '_ {
+        self.state.keys_down.iter().cloned()
+    }
+
+    /// The modifier keys held down as of the last key event.
+    pub fn modifiers(&self) -> Modifiers {
+        self.state.modifiers
+    }
+
+    /// The cell the mouse is over, or `None` if it isn't over the grid.
+    pub fn mouse_cell(&self) -> Option<Point2<usize>> {
+        self.state.mouse_cell
+    }
+
+    /// Whether the mouse button was pressed over the grid and is still held down.
+    pub fn is_mouse_down(&self, button: MouseButton) -> bool {
+        self.state.mouse_buttons.contains(&button)
+    }
+
+    /// The current size of the grid, in cells.
+    pub fn grid_size(&self) -> Point2<usize> {
+        self.state.grid_size
+    }
+
+    /// Whether the window is (or is about to be) fullscreen.
+    pub fn is_fullscreen(&self) -> bool {
+        self.state.fullscreen
+    }
+
+    /// Switch the window to borderless fullscreen on its current monitor, or back again. Has no
+    /// effect in the terminal.
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.state.fullscreen = fullscreen;
+    }
+
+    /// The time between the previous update and the current one.
+    ///
+    /// With `UpdateMode::Fixed` this is always the tick duration.
+    pub fn delta(&self) -> Duration {
+        self.state.delta
+    }
+
+    /// The total of all the update deltas so far, i.e. how long the game has been running.
+    pub fn elapsed(&self) -> Duration {
+        self.state.elapsed
+    }
+
+    /// The number of updates before this one.
+    pub fn tick(&self) -> u64 {
+        self.state.tick
+    }
+
+    pub fn update_mode(&self) -> UpdateMode {
+        self.state.update_mode
+    }
+
+    /// Change how often `App::update` is called, from the next update on.
+    pub fn set_update_mode(&mut self, mode: UpdateMode) {
+        self.state.update_mode = mode;
+    }
+
+    /// Ask for another update even if there is no input, e.g. to play an animation in
+    /// `UpdateMode::WaitForInput`. Has no effect with a fixed tick rate.
+    pub fn request_update(&mut self) {
+        self.state.update_requested = true;
     }
 }
 
-impl IndexMut<Point2<usize>> for Frame {
-    fn index_mut(&mut self, pos: Point2<usize>) -> &mut Self::Output {
-        self.get_mut(pos)
+/// Run the app with the given configuration. Use `Config::default()` to open a window and render
+/// using wgpu.
+///
+/// Returns the exit code the app asked for with `Context::exit_with` (0 if it just exited or was
+/// closed), which can be passed on to `std::process::exit`.
+pub fn run<A>(app: A, config: Config) -> Result<i32, Error>
+where
+    A: App,
+{
+    match config.backend {
+        Backend::Wgpu => backend::gpu::run(app, config),
+        Backend::Headless { ticks } => backend::headless::run(app, ticks),
+        Backend::Terminal(color_mode) => backend::term::run(app, color_mode, config),
     }
 }
@@ -0,0 +1,373 @@
+//! Inline color markup for printed text.
+//!
+//! Tags in square brackets change the colors of the text that follows them, until a matching
+//! `[/]`:
+//!
+//! ```text
+//! You hit the [fg=red]orc[/] for [fg=#ffcc00 bg=rgb(64, 0, 0)]5[/] damage
+//! ```
+//!
+//! A tag can set `fg`, `bg` or both, using any color syntax `TileSet` attributes accept. Tags nest,
+//! and `[/]` closes the innermost one. Write `[[` for a literal `[`.
+use crate::{text::StyledChar, Align, Color, Frame, Rect};
+use mint::Point2;
+use std::{error::Error, fmt};
+
+/// A run of text in a single style. `None` means the default color passed to the print method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+/// An error parsing markup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkupError {
+    /// The byte offset in the markup where the error was found.
+    pub pos: usize,
+    pub msg: String,
+}
+
+impl MarkupError {
+    fn new(pos: usize, msg: impl Into<String>) -> Self {
+        MarkupError {
+            pos,
+            msg: msg.into(),
+        }
+    }
+}
+
+impl fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "at byte {}: {}", self.pos, self.msg)
+    }
+}
+
+impl Error for MarkupError {}
+
+/// The colors set by an open tag.
+#[derive(Debug, Copy, Clone, Default)]
+struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+}
+
+/// Split markup into spans of text, each with the colors in effect for it.
+///
+/// Adjacent text in the same style is a single span, and there are no empty spans.
+pub fn parse(markup: &str) -> Result<Vec<Span>, MarkupError> {
+    let mut spans = Vec::new();
+    // the style of each open tag, innermost last
+    let mut stack: Vec<Style> = Vec::new();
+    let mut text = String::new();
+    let mut rest = markup;
+    while let Some(open) = rest.find('[') {
+        text.push_str(&rest[..open]);
+        let pos = markup.len() - rest.len() + open;
+        let after = &rest[open + 1..];
+        if let Some(after) = after.strip_prefix('[') {
+            text.push('[');
+            rest = after;
+            continue;
+        }
+        let close = after
+            .find(']')
+            .ok_or_else(|| MarkupError::new(pos, "unclosed `[`"))?;
+        let tag = &after[..close];
+        rest = &after[close + 1..];
+
+        let current = stack.last().cloned().unwrap_or_default();
+        push_span(&mut spans, &mut text, current);
+        if tag == "/" {
+            if stack.pop().is_none() {
+                return Err(MarkupError::new(pos, "`[/]` without an open tag"));
+            }
+        } else {
+            stack.push(parse_tag(tag, pos + 1, current)?);
+        }
+    }
+    text.push_str(rest);
+    push_span(
+        &mut spans,
+        &mut text,
+        stack.last().cloned().unwrap_or_default(),
+    );
+    Ok(spans)
+}
+
+/// Finish the current run of text, merging it into the last span if the style is the same.
+fn push_span(spans: &mut Vec<Span>, text: &mut String, style: Style) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(last) = spans.last_mut() {
+        if last.fg == style.fg && last.bg == style.bg {
+            last.text.push_str(text);
+            text.clear();
+            return;
+        }
+    }
+    spans.push(Span {
+        text: std::mem::take(text),
+        fg: style.fg,
+        bg: style.bg,
+    });
+}
+
+/// Parse the inside of a tag like `fg=red bg=#000000`. `pos` is the byte offset of the tag, for
+/// errors. Anything the tag doesn't set is inherited from `outer`.
+fn parse_tag(tag: &str, pos: usize, outer: Style) -> Result<Style, MarkupError> {
+    let mut style = outer;
+    let attrs = split_attrs(tag);
+    if attrs.is_empty() {
+        return Err(MarkupError::new(pos, "empty tag"));
+    }
+    for (offset, attr) in attrs {
+        let pos = pos + offset;
+        let eq = attr.find('=').ok_or_else(|| {
+            MarkupError::new(
+                pos,
+                format!("expected `fg=..` or `bg=..`, found `{}`", attr),
+            )
+        })?;
+        let (name, value) = (&attr[..eq], &attr[eq + 1..]);
+        let slot = match name {
+            "fg" => &mut style.fg,
+            "bg" => &mut style.bg,
+            _ => {
+                return Err(MarkupError::new(
+                    pos,
+                    format!("unknown attribute `{}`", name),
+                ))
+            }
+        };
+        let color = Color::parse(value).map_err(|e| {
+            MarkupError::new(
+                pos + eq + 1 + e.span().start,
+                format!("invalid color `{}`: {}", value, e),
+            )
+        })?;
+        *slot = Some(color);
+    }
+    Ok(style)
+}
+
+/// Split a tag at whitespace that isn't inside parentheses (so `rgb(1, 2, 3)` stays whole),
+/// returning each attribute with its byte offset in the tag.
+fn split_attrs(tag: &str) -> Vec<(usize, &str)> {
+    let mut attrs = Vec::new();
+    let mut depth = 0;
+    let mut start = None;
+    for (i, ch) in tag.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => (),
+        }
+        if ch.is_whitespace() && depth <= 0 {
+            if let Some(s) = start.take() {
+                attrs.push((s, &tag[s..i]));
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        attrs.push((s, &tag[s..]));
+    }
+    attrs
+}
+
+/// The characters of the spans, with `fg` and `bg` filled in where the markup didn't set them.
+fn styled_chars(spans: &[Span], fg: Color, bg: Color) -> Vec<StyledChar> {
+    spans
+        .iter()
+        .flat_map(|span| {
+            let (span_fg, span_bg) = (span.fg.unwrap_or(fg), span.bg.unwrap_or(bg));
+            span.text.chars().map(move |ch| (ch, span_fg, span_bg))
+        })
+        .collect()
+}
+
+impl Frame {
+    /// Like `print`, but with colors given by markup in the text. `fg` and `bg` are used for text
+    /// outside any tag.
+    pub fn print_markup(
+        &mut self,
+        pos: Point2<usize>,
+        markup: &str,
+        fg: Color,
+        bg: Color,
+    ) -> Result<usize, MarkupError> {
+        let text = styled_chars(&parse(markup)?, fg, bg);
+        let clip = self.rect();
+        Ok(self.print_styled(pos, text, clip))
+    }
+
+    /// Like `print_wrapped`, but with colors given by markup in the text. `fg` and `bg` are used
+    /// for text outside any tag.
+    pub fn print_markup_wrapped(
+        &mut self,
+        rect: Rect,
+        markup: &str,
+        align: Align,
+        fg: Color,
+        bg: Color,
+    ) -> Result<usize, MarkupError> {
+        let text = styled_chars(&parse(markup)?, fg, bg);
+        let clip = self.rect();
+        Ok(self.print_styled_wrapped(rect, &text, align, clip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::screen;
+
+    fn span(text: &str, fg: Option<Color>, bg: Option<Color>) -> Span {
+        Span {
+            text: text.to_string(),
+            fg,
+            bg,
+        }
+    }
+
+    #[test]
+    fn plain_text() {
+        assert_eq!(parse("").unwrap(), []);
+        assert_eq!(parse("hello").unwrap(), [span("hello", None, None)]);
+        assert_eq!(parse("a]b").unwrap(), [span("a]b", None, None)]);
+    }
+
+    #[test]
+    fn closing_tags_reset_the_style() {
+        let red = Some(Color::RED);
+        assert_eq!(
+            parse("a[fg=red]b[/]c").unwrap(),
+            [
+                span("a", None, None),
+                span("b", red, None),
+                span("c", None, None)
+            ]
+        );
+        // tags left open last to the end
+        assert_eq!(parse("[bg=red]a").unwrap(), [span("a", None, red)]);
+    }
+
+    #[test]
+    fn nested_tags() {
+        let (red, blue) = (Some(Color::RED), Some(Color::BLUE));
+        assert_eq!(
+            parse("[fg=red]a[bg=blue]b[fg=blue]c[/]d[/]e[/]f").unwrap(),
+            [
+                span("a", red, None),
+                span("b", red, blue),
+                span("c", blue, blue),
+                span("d", red, blue),
+                span("e", red, None),
+                span("f", None, None),
+            ]
+        );
+        // runs in the same style are merged, and empty tags make no spans
+        assert_eq!(
+            parse("[fg=red]a[/][fg=red]b[fg=red][/][/]").unwrap(),
+            [span("ab", red, None)]
+        );
+        assert_eq!(
+            parse("[fg=red bg=rgb(0, 0, 255)]a").unwrap(),
+            [span("a", red, blue)]
+        );
+    }
+
+    #[test]
+    fn escaped_brackets() {
+        assert_eq!(parse("a[[b").unwrap(), [span("a[b", None, None)]);
+        assert_eq!(parse("[[fg=red]").unwrap(), [span("[fg=red]", None, None)]);
+        assert_eq!(
+            parse("[fg=red][[[/]").unwrap(),
+            [span("[", Some(Color::RED), None)]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let tests = [
+            ("a[/]", 1, "`[/]` without an open tag"),
+            ("[fg=red]a[/][/]", 12, "`[/]` without an open tag"),
+            ("ab[fg=red", 2, "unclosed `[`"),
+            ("[[[", 2, "unclosed `[`"),
+            ("[]", 1, "empty tag"),
+            (
+                "x[fg=nope]",
+                5,
+                "invalid color `nope`: unknown color `nope`",
+            ),
+            (
+                "[fg=#12g4]",
+                7,
+                "invalid color `#12g4`: `g` is not a hex digit",
+            ),
+            ("[fg=red size=3]", 8, "unknown attribute `size`"),
+            (
+                "[fg=red bold]",
+                8,
+                "expected `fg=..` or `bg=..`, found `bold`",
+            ),
+        ];
+        for &(markup, pos, msg) in &tests {
+            assert_eq!(
+                parse(markup),
+                Err(MarkupError::new(pos, msg)),
+                "{:?}",
+                markup
+            );
+        }
+        assert_eq!(
+            MarkupError::new(2, "unclosed `[`").to_string(),
+            "at byte 2: unclosed `[`"
+        );
+    }
+
+    #[test]
+    fn printing_markup() {
+        let mut frame = Frame::new(Point2 { x: 5, y: 1 });
+        let len = frame
+            .print_markup(
+                Point2 { x: 1, y: 0 },
+                "a[fg=red]b[/]c",
+                Color::WHITE,
+                Color::BLACK,
+            )
+            .unwrap();
+        assert_eq!(len, 3);
+        assert_eq!(screen(&frame), " abc ");
+        let fgs: Vec<Color> = (1..4).map(|x| frame[(x, 0)].fg).collect();
+        assert_eq!(fgs, [Color::WHITE, Color::RED, Color::WHITE]);
+        assert!((1..4).all(|x| frame[(x, 0)].bg == Color::BLACK));
+
+        // nothing is drawn if the markup is bad
+        let err = frame.print_markup(Point2 { x: 0, y: 0 }, "xy[/]", Color::WHITE, Color::BLACK);
+        assert_eq!(err.unwrap_err().pos, 2);
+        assert_eq!(screen(&frame), " abc ");
+    }
+
+    #[test]
+    fn printing_wrapped_markup() {
+        let mut frame = Frame::new(Point2 { x: 5, y: 2 });
+        let lines = frame
+            .print_markup_wrapped(
+                frame.rect(),
+                "[bg=red]one [fg=blue]two[/][/]",
+                Align::Right,
+                Color::WHITE,
+                Color::BLACK,
+            )
+            .unwrap();
+        assert_eq!(lines, 2);
+        assert_eq!(screen(&frame), "  one\n  two");
+        assert_eq!(frame[(2, 1)].fg, Color::BLUE);
+        assert_eq!(frame[(2, 1)].bg, Color::RED);
+    }
+}
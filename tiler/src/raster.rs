@@ -0,0 +1,327 @@
+//! Software rasterization of a `Frame` into an RGBA image.
+//!
+//! This doesn't need a GPU or a window, so it can be used anywhere (for example on CI machines).
+//! Glyphs are laid out the same way as in the wgpu renderer, so the output should look the same.
+use crate::{text::WIDE_CHAR_TAIL, Color, Frame, FONT};
+use mint::Point2;
+use rusttype::{point, Font, Scale};
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    path::Path,
+};
+
+/// Default width of a cell in pixels.
+pub(crate) const CELL_WIDTH: u32 = 10;
+/// Default height of a cell in pixels.
+pub(crate) const CELL_HEIGHT: u32 = CELL_WIDTH * 2;
+
+/// An image with 8 bits per channel RGBA pixels, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Image {
+    /// Create an image filled with a single color.
+    pub fn new(width: u32, height: u32, color: [u8; 4]) -> Self {
+        let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+        for _ in 0..width * height {
+            data.extend_from_slice(&color);
+        }
+        Image {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Wrap existing RGBA pixel data, 4 bytes per pixel starting at the top-left.
+    ///
+    /// Returns `None` if `data` is the wrong length for the size.
+    pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> Option<Self> {
+        if data.len() != width as usize * height as usize * 4 {
+            return None;
+        }
+        Some(Image {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// The width of the image in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the image in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The raw pixel data, 4 bytes per pixel, starting at the top-left.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the color of the pixel at `(x, y)`.
+    ///
+    /// Panics if the pixel is outside the image.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let idx = self.idx(x, y);
+        let mut px = [0; 4];
+        px.copy_from_slice(&self.data[idx..idx + 4]);
+        px
+    }
+
+    /// Write the image to a PNG file.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width, self.height);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.data)?;
+        Ok(())
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        let idx = self.idx(x, y);
+        self.data[idx..idx + 4].copy_from_slice(&color);
+    }
+
+    fn idx(&self, x: u32, y: u32) -> usize {
+        assert!(x < self.width && y < self.height, "pixel out of bounds");
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+}
+
+/// Draws frames into images using the bundled font.
+pub struct Rasterizer {
+    font: Font<'static>,
+    cell_size: Point2<u32>,
+}
+
+impl Rasterizer {
+    /// Create a rasterizer with the default cell size (10x20 pixels).
+    pub fn new() -> Self {
+        Self::with_cell_size(Point2 {
+            x: CELL_WIDTH,
+            y: CELL_HEIGHT,
+        })
+    }
+
+    /// Create a rasterizer where each cell is `cell_size` pixels.
+    pub fn with_cell_size(cell_size: Point2<u32>) -> Self {
+        // The font is compiled in, so if it doesn't parse that's a bug.
+        let font = Font::from_bytes(FONT).expect("bundled font is valid");
+        Rasterizer { font, cell_size }
+    }
+
+    /// The size of a single cell in pixels.
+    pub fn cell_size(&self) -> Point2<u32> {
+        self.cell_size
+    }
+
+    /// Draw the frame to a new image.
+    ///
+    /// The image is `frame.size() * cell_size()` pixels, and starts out opaque black, the same as
+    /// the window.
+    pub fn render(&self, frame: &Frame) -> Image {
+        let size = frame.size();
+        let Point2 {
+            x: cell_w,
+            y: cell_h,
+        } = self.cell_size;
+        let mut image = Image::new(
+            size.x as u32 * cell_w,
+            size.y as u32 * cell_h,
+            [0, 0, 0, 255],
+        );
+        // Match the glyph scale used by the wgpu renderer.
+        let scale = Scale {
+            x: cell_w as f32 * 2.0,
+            y: cell_h as f32,
+        };
+        let ascent = self.font.v_metrics(scale).ascent;
+        for x in 0..size.x {
+            for y in 0..size.y {
+                let ch = frame.get(Point2 { x, y });
+                let left = x as u32 * cell_w;
+                let top = y as u32 * cell_h;
+                // background
+                if !ch.bg.is_transparent() {
+                    for py in top..top + cell_h {
+                        for px in left..left + cell_w {
+                            blend(&mut image, px, py, ch.bg, 1.0);
+                        }
+                    }
+                }
+                // foreground
+                if !ch.fg.is_transparent() && ch.ch != WIDE_CHAR_TAIL {
+                    let glyph = self
+                        .font
+                        .glyph(ch.ch)
+                        .scaled(scale)
+                        .positioned(point(left as f32, top as f32 + ascent));
+                    if let Some(bb) = glyph.pixel_bounding_box() {
+                        glyph.draw(|gx, gy, coverage| {
+                            let px = bb.min.x + gx as i32;
+                            let py = bb.min.y + gy as i32;
+                            if px >= 0
+                                && py >= 0
+                                && (px as u32) < image.width
+                                && (py as u32) < image.height
+                            {
+                                blend(&mut image, px as u32, py as u32, ch.fg, coverage);
+                            }
+                        });
+                    }
+                }
+            }
+        }
+        image
+    }
+}
+
+impl Default for Rasterizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Frame {
+    /// Render the frame with the default `Rasterizer` and save it as a PNG file.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        Rasterizer::new().render(self).save_png(path)
+    }
+}
+
+/// Draw `color` over the pixel at `(x, y)`, scaling its alpha by `coverage`.
+fn blend(image: &mut Image, x: u32, y: u32, color: Color, coverage: f32) {
+    let color = color.to_f32();
+    let dst = image.pixel(x, y);
+    let alpha = (color[3] * coverage).clamp(0.0, 1.0);
+    let mut out = [0; 4];
+    for i in 0..3 {
+        let d = dst[i] as f32 / 255.0;
+        out[i] = ((d + (color[i] - d) * alpha) * 255.0).round() as u8;
+    }
+    out[3] = 255;
+    image.set_pixel(x, y, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Char;
+    use std::fs;
+
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+
+    /// Every pixel of the cell at `(x, y)`.
+    fn cell_pixels(image: &Image, cell_size: Point2<u32>, x: u32, y: u32) -> Vec<[u8; 4]> {
+        let (left, top) = (x * cell_size.x, y * cell_size.y);
+        (top..top + cell_size.y)
+            .flat_map(|py| (left..left + cell_size.x).map(move |px| (px, py)))
+            .map(|(px, py)| image.pixel(px, py))
+            .collect()
+    }
+
+    #[test]
+    fn images_are_the_size_of_the_frame_in_cells() {
+        let frame = Frame::new(Point2 { x: 3, y: 2 });
+        let image = Rasterizer::new().render(&frame);
+        assert_eq!((image.width(), image.height()), (30, 40));
+        let image = Rasterizer::with_cell_size(Point2 { x: 4, y: 7 }).render(&frame);
+        assert_eq!((image.width(), image.height()), (12, 14));
+        assert_eq!(image.data().len(), 12 * 14 * 4);
+        assert!(image.data().chunks(4).all(|px| px == BLACK));
+        let image = Rasterizer::new().render(&Frame::new(Point2 { x: 0, y: 0 }));
+        assert_eq!((image.width(), image.height()), (0, 0));
+    }
+
+    #[test]
+    fn backgrounds_fill_their_cell() {
+        let cell_size = Point2 { x: 4, y: 6 };
+        let mut frame = Frame::new(Point2 { x: 3, y: 2 });
+        frame[(1, 0)].bg = Color::rgb(10, 200, 30);
+        frame[(2, 1)].bg = Color::RED.with_alpha(128);
+        let image = Rasterizer::with_cell_size(cell_size).render(&frame);
+        let pixels = |x, y| cell_pixels(&image, cell_size, x, y);
+        assert!(pixels(1, 0).iter().all(|&px| px == [10, 200, 30, 255]));
+        // translucent backgrounds are blended over black
+        assert!(pixels(2, 1).iter().all(|&px| px == [128, 0, 0, 255]));
+        for &(x, y) in &[(0, 0), (2, 0), (0, 1), (1, 1)] {
+            assert!(pixels(x, y).iter().all(|&px| px == BLACK), "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn glyphs_are_drawn_in_their_cell() {
+        let rasterizer = Rasterizer::new();
+        let cell_size = rasterizer.cell_size();
+        let mut frame = Frame::new(Point2 { x: 3, y: 1 });
+        let hash = Char {
+            ch: '#',
+            fg: Color::WHITE,
+            bg: Color::TRANSPARENT,
+        };
+        frame[(0, 0)] = hash;
+        frame[(1, 0)] = Char {
+            fg: Color::TRANSPARENT,
+            ..hash
+        };
+        frame[(2, 0)] = Char {
+            ch: WIDE_CHAR_TAIL,
+            ..hash
+        };
+        let image = rasterizer.render(&frame);
+        assert!(cell_pixels(&image, cell_size, 0, 0)
+            .iter()
+            .any(|&px| px != BLACK));
+        for x in 1..3 {
+            assert!(cell_pixels(&image, cell_size, x, 0)
+                .iter()
+                .all(|&px| px == BLACK));
+        }
+    }
+
+    #[test]
+    fn wrapping_pixel_data() {
+        assert_eq!(Image::from_rgba(2, 2, vec![0; 15]), None);
+        assert_eq!(Image::from_rgba(2, 2, vec![0; 17]), None);
+        assert_eq!(Image::from_rgba(0, 5, vec![]).map(|i| i.height()), Some(5));
+        let data: Vec<u8> = (0..16).collect();
+        let image = Image::from_rgba(2, 2, data.clone()).unwrap();
+        assert_eq!(image.pixel(1, 0), [4, 5, 6, 7]);
+        assert_eq!(image.pixel(0, 1), [8, 9, 10, 11]);
+        assert_eq!(image.data(), &data[..]);
+    }
+
+    #[test]
+    fn pngs_round_trip() {
+        let mut frame = Frame::new(Point2 { x: 2, y: 2 });
+        frame.print(Point2 { x: 0, y: 0 }, "a", Color::WHITE, Color::BLUE);
+        frame[(1, 1)].bg = Color::GREEN.with_alpha(100);
+        let image = Rasterizer::new().render(&frame);
+
+        let dir = std::env::temp_dir().join(format!("tiler-raster-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("frame.png");
+        image.save_png(&path).unwrap();
+        let (info, mut reader) = png::Decoder::new(File::open(&path).unwrap())
+            .read_info()
+            .unwrap();
+        let mut data = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut data).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!((info.width, info.height), (image.width(), image.height()));
+        assert_eq!(info.color_type, png::ColorType::RGBA);
+        assert_eq!(Image::from_rgba(info.width, info.height, data), Some(image));
+    }
+}
@@ -0,0 +1,337 @@
+//! Drawing lines and shapes on a `Frame`.
+//!
+//! Shapes are measured in cells, so a circle is only round if the cells are square. Positions are
+//! signed, and anything outside the frame is skipped, so shapes can be partly off screen.
+use crate::{CellPos, Frame, Rect, TileSet};
+use mint::Point2;
+
+impl Frame {
+    /// Draw a straight line between two cells, including both ends. Only the part of the line
+    /// inside the frame is worked out, so the ends can be anywhere.
+    pub fn draw_line(&mut self, from: Point2<i32>, to: Point2<i32>, tile: impl TileSet) {
+        let ch = tile.to_char();
+        let from = (i128::from(from.x), i128::from(from.y));
+        let to = (i128::from(to.x), i128::from(to.y));
+        if (to.0 - from.0).abs() >= (to.1 - from.1).abs() {
+            for (x, y) in line_cells(from, to, self.size.x) {
+                self.set((x, y), ch);
+            }
+        } else {
+            for (y, x) in line_cells((from.1, from.0), (to.1, to.0), self.size.y) {
+                self.set((x, y), ch);
+            }
+        }
+    }
+
+    /// Draw a horizontal run of `len` cells, going right from `start`.
+    pub fn draw_hline(&mut self, start: Point2<i32>, len: usize, tile: impl TileSet) {
+        let ch = tile.to_char();
+        for x in 0..len as i32 {
+            self.set((start.x + x, start.y), ch);
+        }
+    }
+
+    /// Draw a vertical run of `len` cells, going down from `start`.
+    pub fn draw_vline(&mut self, start: Point2<i32>, len: usize, tile: impl TileSet) {
+        let ch = tile.to_char();
+        for y in 0..len as i32 {
+            self.set((start.x, start.y + y), ch);
+        }
+    }
+
+    /// Set every cell in `rect` to `tile`.
+    pub fn fill_rect(&mut self, rect: Rect, tile: impl TileSet) {
+        let ch = tile.to_char();
+        let rect = rect.intersection(self.rect());
+        for x in rect.top_left.x..rect.bottom_right.x {
+            for y in rect.top_left.y..rect.bottom_right.y {
+                self[(x, y)] = ch;
+            }
+        }
+    }
+
+    /// Set the cells around the inside edge of `rect` to `tile`.
+    pub fn draw_rect(&mut self, rect: Rect, tile: impl TileSet) {
+        if rect.is_empty() {
+            return;
+        }
+        let ch = tile.to_char();
+        let Rect {
+            top_left,
+            bottom_right,
+        } = rect;
+        for x in top_left.x..bottom_right.x {
+            self.set((x, top_left.y), ch);
+            self.set((x, bottom_right.y - 1), ch);
+        }
+        for y in top_left.y..bottom_right.y {
+            self.set((top_left.x, y), ch);
+            self.set((bottom_right.x - 1, y), ch);
+        }
+    }
+
+    /// Draw the outline of a circle.
+    pub fn draw_circle(&mut self, center: Point2<i32>, radius: u32, tile: impl TileSet) {
+        self.draw_ellipse(
+            center,
+            Point2 {
+                x: radius,
+                y: radius,
+            },
+            tile,
+        )
+    }
+
+    /// Draw a filled circle.
+    pub fn fill_circle(&mut self, center: Point2<i32>, radius: u32, tile: impl TileSet) {
+        self.fill_ellipse(
+            center,
+            Point2 {
+                x: radius,
+                y: radius,
+            },
+            tile,
+        )
+    }
+
+    /// Draw the outline of an ellipse with the given horizontal and vertical radii. The outline
+    /// is the edge cells of `fill_ellipse`, so there are no gaps in it.
+    pub fn draw_ellipse(&mut self, center: Point2<i32>, radii: Point2<u32>, tile: impl TileSet) {
+        let ch = tile.to_char();
+        let inside = |dx: i32, dy: i32| in_ellipse(dx, dy, radii);
+        let (rx, ry) = (radii.x as i32, radii.y as i32);
+        for dy in -ry..=ry {
+            for dx in -rx..=rx {
+                let edge = !inside(dx - 1, dy)
+                    || !inside(dx + 1, dy)
+                    || !inside(dx, dy - 1)
+                    || !inside(dx, dy + 1);
+                if inside(dx, dy) && edge {
+                    self.set((center.x + dx, center.y + dy), ch);
+                }
+            }
+        }
+    }
+
+    /// Draw a filled ellipse with the given horizontal and vertical radii.
+    pub fn fill_ellipse(&mut self, center: Point2<i32>, radii: Point2<u32>, tile: impl TileSet) {
+        let ch = tile.to_char();
+        let (rx, ry) = (radii.x as i32, radii.y as i32);
+        for dy in -ry..=ry {
+            for dx in -rx..=rx {
+                if in_ellipse(dx, dy, radii) {
+                    self.set((center.x + dx, center.y + dy), ch);
+                }
+            }
+        }
+    }
+
+    /// Replace the cell at `start`, and every cell connected to it (horizontally or vertically)
+    /// that is the same as it, with `tile`.
+    pub fn flood_fill(&mut self, start: impl CellPos, tile: impl TileSet) {
+        let ch = tile.to_char();
+        let start = match start.to_cell() {
+            Some(start) => start,
+            None => return,
+        };
+        let target = match self.try_get(start) {
+            Some(&target) if target != ch => target,
+            _ => return,
+        };
+        let mut stack = vec![start];
+        while let Some(pos) = stack.pop() {
+            match self.try_get_mut(pos) {
+                Some(cell) if *cell == target => *cell = ch,
+                _ => continue,
+            }
+            let Point2 { x, y } = pos;
+            stack.push(Point2 { x: x + 1, y });
+            stack.push(Point2 { x, y: y + 1 });
+            if x > 0 {
+                stack.push(Point2 { x: x - 1, y });
+            }
+            if y > 0 {
+                stack.push(Point2 { x, y: y - 1 });
+            }
+        }
+    }
+}
+
+/// The cells of a line that is at least as long along its major axis as along its minor one, as
+/// `(major, minor)` pairs, leaving out any with a major coordinate outside `0..len`.
+///
+/// Each cell is the one nearest the exact line on the minor axis, like Bresenham's algorithm, with
+/// halves rounded the same way whichever end the line starts from. The maths is done in `i128`,
+/// so the ends can be as far apart as an `i32` allows.
+fn line_cells(
+    from: (i128, i128),
+    to: (i128, i128),
+    len: usize,
+) -> impl Iterator<Item = (i32, i32)> {
+    let ((a0, b0), (a1, b1)) = if from.0 <= to.0 {
+        (from, to)
+    } else {
+        (to, from)
+    };
+    let (da, db) = (a1 - a0, b1 - b0);
+    (a0.max(0)..=a1.min(len as i128 - 1)).map(move |a| {
+        let b = if da == 0 {
+            b0
+        } else {
+            // b0 + (a - a0) * db / da, rounded to the nearest cell
+            (2 * (b0 * da + (a - a0) * db) + da).div_euclid(2 * da)
+        };
+        // both lie between the ends, so they fit
+        (a as i32, b as i32)
+    })
+}
+
+/// Whether a cell offset from the center is inside an ellipse. The radii are measured to the
+/// outside edge of the last cell, so an ellipse with a radius of 0 is a single cell wide.
+fn in_ellipse(dx: i32, dy: i32, radii: Point2<u32>) -> bool {
+    // doubled so the half cell stays an integer
+    let (dx, dy) = (2 * dx as i64, 2 * dy as i64);
+    let (rx, ry) = (2 * radii.x as i64 + 1, 2 * radii.y as i64 + 1);
+    dx * dx * ry * ry + dy * dy * rx * rx <= rx * rx * ry * ry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing::screen, Char};
+
+    fn tile(ch: char) -> Char {
+        Char {
+            ch,
+            ..Char::default()
+        }
+    }
+
+    fn line(width: usize, height: usize, from: (i32, i32), to: (i32, i32)) -> String {
+        let mut frame = Frame::new(Point2 {
+            x: width,
+            y: height,
+        });
+        let point = |(x, y)| Point2 { x, y };
+        frame.draw_line(point(from), point(to), tile('*'));
+        screen(&frame)
+    }
+
+    #[test]
+    fn lines() {
+        let tests = [
+            ((0, 1), (4, 1), "     \n*****\n     "),
+            ((2, 0), (2, 2), "  *  \n  *  \n  *  "),
+            ((0, 0), (2, 2), "*    \n *   \n  *  "),
+            ((4, 0), (2, 2), "    *\n   * \n  *  "),
+            ((0, 0), (4, 2), "*    \n **  \n   **"),
+            ((1, 0), (2, 2), " *   \n  *  \n  *  "),
+            ((1, 1), (1, 1), "     \n *   \n     "),
+            // ends off the frame
+            ((-2, -2), (2, 2), "*    \n *   \n  *  "),
+            ((-5, 1), (9, 1), "     \n*****\n     "),
+            ((5, 0), (9, 2), "     \n     \n     "),
+        ];
+        for &(from, to, expected) in &tests {
+            assert_eq!(line(5, 3, from, to), expected, "{:?} to {:?}", from, to);
+            assert_eq!(line(5, 3, to, from), expected, "{:?} to {:?}", to, from);
+        }
+    }
+
+    #[test]
+    fn lines_between_far_apart_ends() {
+        const MIN: i32 = i32::MIN;
+        const MAX: i32 = i32::MAX;
+        assert_eq!(line(3, 3, (MIN, 1), (MAX, 1)), "   \n***\n   ");
+        assert_eq!(line(3, 3, (1, MIN), (1, MAX)), " * \n * \n * ");
+        assert_eq!(line(3, 3, (MIN, MIN), (MAX, MAX)), "*  \n * \n  *");
+        assert_eq!(line(3, 3, (MAX, MIN), (MIN, MAX)), "   \n   \n   ");
+        assert_eq!(line(3, 3, (MIN, 0), (MAX, 2)), "   \n***\n   ");
+    }
+
+    #[test]
+    fn ellipses_are_symmetrical() {
+        for &(radii, fill) in &[
+            ((4, 2), false),
+            ((4, 2), true),
+            ((3, 3), false),
+            ((0, 2), true),
+        ] {
+            let mut frame = Frame::new(Point2 { x: 11, y: 7 });
+            let (center, radii) = (
+                Point2 { x: 5, y: 3 },
+                Point2 {
+                    x: radii.0,
+                    y: radii.1,
+                },
+            );
+            if fill {
+                frame.fill_ellipse(center, radii, tile('*'));
+            } else {
+                frame.draw_ellipse(center, radii, tile('*'));
+            }
+            let screen = screen(&frame);
+            let rows: Vec<&str> = screen.lines().collect();
+            let flipped: Vec<&str> = rows.iter().rev().cloned().collect();
+            assert_eq!(rows, flipped, "{:?}\n{}", radii, screen);
+            for row in &rows {
+                assert_eq!(
+                    row.chars().rev().collect::<String>(),
+                    *row,
+                    "{:?}\n{}",
+                    radii,
+                    screen
+                );
+            }
+            // the middle row reaches the radius on both sides
+            let rx = radii.x as usize;
+            assert_eq!(rows[3].find('*'), Some(5 - rx));
+            assert_eq!(rows[3].rfind('*'), Some(5 + rx));
+        }
+
+        let mut frame = Frame::new(Point2 { x: 9, y: 5 });
+        frame.draw_ellipse(Point2 { x: 4, y: 2 }, Point2 { x: 4, y: 2 }, tile('*'));
+        assert_eq!(
+            screen(&frame),
+            "  *****  \n**     **\n*       *\n**     **\n  *****  "
+        );
+        let mut frame = Frame::new(Point2 { x: 3, y: 3 });
+        frame.draw_circle(Point2 { x: 1, y: 1 }, 0, tile('*'));
+        assert_eq!(screen(&frame), "   \n * \n   ");
+    }
+
+    #[test]
+    fn flood_fill_stops_at_walls_and_the_edge() {
+        let mut frame = Frame::new(Point2 { x: 6, y: 5 });
+        frame.draw_rect(Rect::from_parts(0, 0, 5, 4), tile('#'));
+        frame.set((2, 1), tile('#'));
+        frame.flood_fill((1, 1), tile('.'));
+        assert_eq!(screen(&frame), "##### \n#.#.# \n#...# \n##### \n      ");
+        // outside the walls, it runs along the edges of the frame
+        frame.flood_fill(Point2 { x: 5, y: 4 }, tile('~'));
+        assert_eq!(screen(&frame), "#####~\n#.#.#~\n#...#~\n#####~\n~~~~~~");
+        // starting off the frame, or on the same tile, does nothing
+        frame.flood_fill((-1, 0), tile('!'));
+        frame.flood_fill((6, 0), tile('!'));
+        frame.flood_fill((1, 1), tile('.'));
+        assert!(!screen(&frame).contains('!'));
+    }
+
+    #[test]
+    fn thin_rects() {
+        let tests = [
+            (Rect::from_parts(1, 0, 2, 3), " *  \n *  \n *  "),
+            (Rect::from_parts(0, 1, 3, 2), "    \n*** \n    "),
+            (Rect::from_parts(2, 2, 3, 3), "    \n    \n  * "),
+            (Rect::from_parts(1, 0, 3, 3), " ** \n ** \n ** "),
+            (Rect::from_parts(1, 0, 1, 3), "    \n    \n    "),
+            // partly off the frame
+            (Rect::from_parts(3, 1, 4, 5), "    \n   *\n   *"),
+        ];
+        for &(rect, expected) in &tests {
+            let mut frame = Frame::new(Point2 { x: 4, y: 3 });
+            frame.draw_rect(rect, tile('*'));
+            assert_eq!(screen(&frame), expected, "{:?}", rect);
+        }
+    }
+}
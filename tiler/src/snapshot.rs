@@ -0,0 +1,520 @@
+//! Golden-file snapshots of frames.
+//!
+//! A snapshot is a plain text file with the characters of the frame, followed by a grid of style
+//! keys and a legend saying which foreground and background color each key stands for:
+//!
+//! ```text
+//! size 5x3
+//! |╔═══╗|
+//! |║···║|
+//! |╚═══╝|
+//! styles
+//! |aaaaa|
+//! |abbba|
+//! |aaaaa|
+//! a fg=#808080ff bg=#000000ff
+//! b fg=#ffffffff bg=#000000ff
+//! ```
+//!
+//! The style key `.` is always the default (fully transparent) style and isn't listed in the
+//! legend. Keys are assigned in reading order, so the same frame always gives the same text.
+//!
+//! The cell after a wide character holds `text::WIDE_CHAR_TAIL`, a NUL, which would make git and
+//! diff tools treat the file as binary. It is written as `␀` instead, so every row still has one
+//! `char` per cell, and read back as the tail. This means a frame can't store a real `␀`.
+//!
+//! Set the `TILER_BLESS` environment variable to write new snapshots instead of comparing.
+use crate::{text::WIDE_CHAR_TAIL, Char, Frame};
+use mint::Point2;
+use std::{collections::HashMap, env, error::Error, fmt, fs, path::Path};
+
+/// The environment variable that makes `assert_snapshot` overwrite the golden file.
+pub const BLESS_VAR: &str = "TILER_BLESS";
+
+/// The style key used for cells with transparent foreground and background.
+const DEFAULT_KEY: char = '.';
+
+/// What `text::WIDE_CHAR_TAIL` is written as.
+const WIDE_CHAR_TAIL_KEY: char = '␀';
+
+/// The most differing cells to list when a snapshot doesn't match.
+const MAX_REPORTED: usize = 20;
+
+/// A cell with its colors as raw bytes, which is what the snapshot stores.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct Cell {
+    ch: char,
+    fg: [u8; 4],
+    bg: [u8; 4],
+}
+
+impl Cell {
+    fn from_char(ch: &Char) -> Self {
+        Cell {
+            // control characters would break the line-based format
+            ch: if ch.ch.is_control() && ch.ch != WIDE_CHAR_TAIL {
+                std::char::REPLACEMENT_CHARACTER
+            } else {
+                ch.ch
+            },
+            fg: ch.fg.into(),
+            bg: ch.bg.into(),
+        }
+    }
+
+    fn style(&self) -> ([u8; 4], [u8; 4]) {
+        (self.fg, self.bg)
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} fg={} bg={}", self.ch, hex(self.fg), hex(self.bg))
+    }
+}
+
+fn hex([r, g, b, a]: [u8; 4]) -> String {
+    format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+}
+
+fn parse_hex(input: &str) -> Option<[u8; 4]> {
+    if !input.starts_with('#') || input.len() != 9 {
+        return None;
+    }
+    let mut out = [0; 4];
+    for (i, o) in out.iter_mut().enumerate() {
+        *o = u8::from_str_radix(input.get(1 + 2 * i..3 + 2 * i)?, 16).ok()?;
+    }
+    Some(out)
+}
+
+/// The key for the `n`th style (not counting the default).
+fn style_key(n: usize) -> char {
+    const KEYS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    match KEYS.chars().nth(n) {
+        Some(key) => key,
+        // Run out of ascii, so start using letters from Latin-1 onwards.
+        None => std::char::from_u32(0xc0 + (n - KEYS.len()) as u32).unwrap_or('?'),
+    }
+}
+
+/// An error reading a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotError {
+    /// The line of the snapshot the error was found on, starting at 1.
+    pub line: usize,
+    pub msg: String,
+}
+
+impl SnapshotError {
+    fn new(line: usize, msg: impl Into<String>) -> Self {
+        SnapshotError {
+            line,
+            msg: msg.into(),
+        }
+    }
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.msg)
+    }
+}
+
+impl Error for SnapshotError {}
+
+impl Frame {
+    /// Serialize the frame to the snapshot text format.
+    pub fn to_snapshot(&self) -> String {
+        let size = self.size();
+        let cells = cells(self);
+        let mut styles: Vec<([u8; 4], [u8; 4])> = Vec::new();
+        let mut keys = HashMap::new();
+        keys.insert(Cell::from_char(&Char::default()).style(), DEFAULT_KEY);
+
+        let mut out = format!("size {}x{}\n", size.x, size.y);
+        for row in cells.iter() {
+            out.push('|');
+            out.extend(row.iter().map(|cell| match cell.ch {
+                WIDE_CHAR_TAIL => WIDE_CHAR_TAIL_KEY,
+                ch => ch,
+            }));
+            out.push_str("|\n");
+        }
+        out.push_str("styles\n");
+        for row in cells.iter() {
+            out.push('|');
+            for cell in row {
+                let style = cell.style();
+                let key = match keys.get(&style) {
+                    Some(&key) => key,
+                    None => {
+                        let key = style_key(styles.len());
+                        styles.push(style);
+                        keys.insert(style, key);
+                        key
+                    }
+                };
+                out.push(key);
+            }
+            out.push_str("|\n");
+        }
+        for (n, (fg, bg)) in styles.iter().enumerate() {
+            out.push_str(&format!(
+                "{} fg={} bg={}\n",
+                style_key(n),
+                hex(*fg),
+                hex(*bg)
+            ));
+        }
+        out
+    }
+
+    /// Read a frame back from the snapshot text format.
+    pub fn from_snapshot(input: &str) -> Result<Frame, SnapshotError> {
+        let mut lines = input.lines().enumerate().map(|(n, line)| (n + 1, line));
+        let (line_no, header) = lines
+            .next()
+            .ok_or_else(|| SnapshotError::new(1, "empty snapshot"))?;
+        let size = parse_size(header)
+            .ok_or_else(|| SnapshotError::new(line_no, "expected `size <width>x<height>`"))?;
+
+        let grid_row = |lines: &mut dyn Iterator<Item = (usize, &str)>| {
+            let (line_no, line) = lines
+                .next()
+                .ok_or_else(|| SnapshotError::new(line_no, "unexpected end of snapshot"))?;
+            let row: Vec<char> = line.chars().collect();
+            if row.len() != size.x + 2 || row[0] != '|' || row[row.len() - 1] != '|' {
+                return Err(SnapshotError::new(
+                    line_no,
+                    format!("expected a row of {} cells between `|`s", size.x),
+                ));
+            }
+            Ok((line_no, row[1..row.len() - 1].to_vec()))
+        };
+
+        let mut chars = Vec::with_capacity(size.y);
+        for _ in 0..size.y {
+            chars.push(grid_row(&mut lines)?.1);
+        }
+        match lines.next() {
+            Some((_, "styles")) => (),
+            Some((line_no, _)) => return Err(SnapshotError::new(line_no, "expected `styles`")),
+            None => return Err(SnapshotError::new(line_no, "unexpected end of snapshot")),
+        }
+        let mut style_rows = Vec::with_capacity(size.y);
+        for _ in 0..size.y {
+            style_rows.push(grid_row(&mut lines)?);
+        }
+
+        let mut legend = HashMap::new();
+        legend.insert(DEFAULT_KEY, ([0; 4], [0; 4]));
+        for (line_no, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let style = parse_legend(line)
+                .ok_or_else(|| SnapshotError::new(line_no, "expected `<key> fg=#.. bg=#..`"))?;
+            legend.insert(style.0, (style.1, style.2));
+        }
+
+        let mut frame = Frame::new(size);
+        for (y, (line_no, keys)) in style_rows.into_iter().enumerate() {
+            for (x, key) in keys.into_iter().enumerate() {
+                let (fg, bg) = *legend.get(&key).ok_or_else(|| {
+                    SnapshotError::new(line_no, format!("style `{}` is not in the legend", key))
+                })?;
+                frame[(x, y)] = Char {
+                    ch: match chars[y][x] {
+                        WIDE_CHAR_TAIL_KEY => WIDE_CHAR_TAIL,
+                        ch => ch,
+                    },
+                    fg: fg.into(),
+                    bg: bg.into(),
+                };
+            }
+        }
+        Ok(frame)
+    }
+}
+
+fn cells(frame: &Frame) -> Vec<Vec<Cell>> {
+    let size = frame.size();
+    (0..size.y)
+        .map(|y| {
+            (0..size.x)
+                .map(|x| Cell::from_char(frame.get(Point2 { x, y })))
+                .collect()
+        })
+        .collect()
+}
+
+fn parse_size(line: &str) -> Option<Point2<usize>> {
+    if !line.starts_with("size ") {
+        return None;
+    }
+    let mut dims = line["size ".len()..].splitn(2, 'x');
+    let x = dims.next()?.parse().ok()?;
+    let y = dims.next()?.parse().ok()?;
+    Some(Point2 { x, y })
+}
+
+fn parse_legend(line: &str) -> Option<(char, [u8; 4], [u8; 4])> {
+    let mut parts = line.split(' ');
+    let mut key = parts.next()?.chars();
+    let key = match (key.next(), key.next()) {
+        (Some(key), None) => key,
+        _ => return None,
+    };
+    let fg = parse_hex(field(parts.next()?, "fg=")?)?;
+    let bg = parse_hex(field(parts.next()?, "bg=")?)?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((key, fg, bg))
+}
+
+/// The value of a `name=value` field.
+fn field<'a>(input: &'a str, name: &str) -> Option<&'a str> {
+    input.strip_prefix(name)
+}
+
+/// Describe how `actual` differs from `expected`, or return `None` if they are the same (after
+/// quantizing the colors).
+pub fn diff(expected: &Frame, actual: &Frame) -> Option<String> {
+    let (exp_size, act_size) = (expected.size(), actual.size());
+    if (exp_size.x, exp_size.y) != (act_size.x, act_size.y) {
+        return Some(format!(
+            "frame size differs: expected {}x{}, found {}x{}",
+            exp_size.x, exp_size.y, act_size.x, act_size.y
+        ));
+    }
+    let (expected, actual) = (cells(expected), cells(actual));
+    let mut map = String::new();
+    let mut differences = Vec::new();
+    for (y, (exp_row, act_row)) in expected.iter().zip(actual.iter()).enumerate() {
+        map.push('|');
+        for (x, (exp, act)) in exp_row.iter().zip(act_row.iter()).enumerate() {
+            if exp == act {
+                map.push('.');
+            } else {
+                map.push('X');
+                differences.push(format!("  ({}, {}): expected {}, found {}", x, y, exp, act));
+            }
+        }
+        map.push_str("|\n");
+    }
+    if differences.is_empty() {
+        return None;
+    }
+    let mut out = format!("{} cells differ (marked X):\n{}", differences.len(), map);
+    for line in differences.iter().take(MAX_REPORTED) {
+        out.push_str(line);
+        out.push('\n');
+    }
+    if differences.len() > MAX_REPORTED {
+        out.push_str(&format!(
+            "  ...and {} more\n",
+            differences.len() - MAX_REPORTED
+        ));
+    }
+    Some(out)
+}
+
+/// Compare the frame against the golden file at `path`, panicking with a cell-by-cell diff if they
+/// differ.
+///
+/// If the `TILER_BLESS` environment variable is set, the golden file is (over)written with the
+/// frame instead.
+pub fn assert_snapshot(frame: &Frame, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    let snapshot = frame.to_snapshot();
+    if env::var_os(BLESS_VAR).is_some() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("could not create snapshot directory");
+        }
+        fs::write(path, snapshot).expect("could not write snapshot");
+        return;
+    }
+    let golden = match fs::read_to_string(path) {
+        Ok(golden) => golden,
+        Err(e) => panic!(
+            "could not read snapshot {}: {} (set {}=1 to create it)",
+            path.display(),
+            e,
+            BLESS_VAR
+        ),
+    };
+    let expected = match Frame::from_snapshot(&golden) {
+        Ok(frame) => frame,
+        Err(e) => panic!("invalid snapshot {}: {}", path.display(), e),
+    };
+    if let Some(diff) = diff(&expected, frame) {
+        panic!(
+            "frame does not match snapshot {} (set {}=1 to update it)\n{}",
+            path.display(),
+            BLESS_VAR,
+            diff
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    fn frame(size: (usize, usize), cells: &[((usize, usize), char, Color, Color)]) -> Frame {
+        let mut frame = Frame::new(Point2 {
+            x: size.0,
+            y: size.1,
+        });
+        for &(pos, ch, fg, bg) in cells {
+            frame[pos] = Char { ch, fg, bg };
+        }
+        frame
+    }
+
+    fn sample() -> Frame {
+        frame(
+            (3, 2),
+            &[
+                ((0, 0), '@', Color::WHITE, Color::BLACK),
+                ((1, 0), '#', Color::GRAY, Color::BLACK),
+                ((2, 1), '@', Color::WHITE, Color::BLACK),
+            ],
+        )
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(
+            sample().to_snapshot(),
+            "size 3x2\n\
+             |@# |\n\
+             |  @|\n\
+             styles\n\
+             |ab.|\n\
+             |..a|\n\
+             a fg=#ffffffff bg=#000000ff\n\
+             b fg=#808080ff bg=#000000ff\n"
+        );
+    }
+
+    #[test]
+    fn legend_keys_follow_reading_order() {
+        let styles: Vec<_> = (0..70u8)
+            .map(|n| ((n as usize, 0), 'x', Color::rgb(n, 0, 0), Color::BLACK))
+            .collect();
+        let snapshot = frame((70, 1), &styles).to_snapshot();
+        let keys: String = snapshot.lines().nth(3).unwrap().chars().collect();
+        assert!(keys.starts_with("|abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789"));
+        assert!(keys.ends_with("ÀÁÂÃÄÅÆÇ|"));
+        assert!(snapshot.contains("\nb fg=#010000ff bg=#000000ff\n"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let original = sample();
+        let read = Frame::from_snapshot(&original.to_snapshot()).unwrap();
+        assert_eq!(diff(&original, &read), None);
+        assert_eq!(read.to_snapshot(), original.to_snapshot());
+
+        let blank = Frame::new(Point2 { x: 4, y: 1 });
+        let read = Frame::from_snapshot(&blank.to_snapshot()).unwrap();
+        assert_eq!(diff(&blank, &read), None);
+    }
+
+    #[test]
+    fn wide_characters() {
+        let mut frame = Frame::new(Point2 { x: 4, y: 1 });
+        frame.print(Point2 { x: 0, y: 0 }, "漢x", Color::WHITE, Color::BLACK);
+        assert_eq!(frame[(1, 0)].ch, WIDE_CHAR_TAIL);
+        let snapshot = frame.to_snapshot();
+        assert!(!snapshot.contains('\0'));
+        assert!(snapshot.contains("|漢␀x |"));
+        let read = Frame::from_snapshot(&snapshot).unwrap();
+        assert_eq!(read[(1, 0)].ch, WIDE_CHAR_TAIL);
+        assert_eq!(diff(&frame, &read), None);
+    }
+
+    #[test]
+    fn control_characters_are_replaced() {
+        let frame = frame((2, 1), &[((0, 0), '\n', Color::WHITE, Color::BLACK)]);
+        let read = Frame::from_snapshot(&frame.to_snapshot()).unwrap();
+        assert_eq!(read[(0, 0)].ch, std::char::REPLACEMENT_CHARACTER);
+    }
+
+    #[test]
+    fn read_errors() {
+        let error = |input: &str| Frame::from_snapshot(input).unwrap_err();
+        assert_eq!(error(""), SnapshotError::new(1, "empty snapshot"));
+        assert_eq!(error("size 2by1").line, 1);
+        assert_eq!(error("size 2x1\n|ab|").line, 1);
+        assert_eq!(error("size 2x1\n|abc|\nstyles\n|..|").line, 2);
+        assert_eq!(error("size 2x1\n|ab|\nstyle\n|..|").line, 3);
+        assert_eq!(
+            error("size 2x1\n|ab|\nstyles\n|.a|"),
+            SnapshotError::new(4, "style `a` is not in the legend")
+        );
+        assert_eq!(
+            error("size 2x1\n|ab|\nstyles\n|..|\na fg=#fff bg=#000").line,
+            5
+        );
+    }
+
+    #[test]
+    fn diff_lists_changed_cells() {
+        let expected = sample();
+        let mut actual = sample();
+        actual[(1, 0)].ch = '+';
+        actual[(2, 1)].fg = Color::RED;
+        let diff = diff(&expected, &actual).unwrap();
+        assert!(diff.starts_with("2 cells differ (marked X):\n|.X.|\n|..X|\n"));
+        assert!(diff.contains("(1, 0): expected '#' fg=#808080ff bg=#000000ff, found '+'"));
+        assert!(diff.contains("(2, 1): expected '@' fg=#ffffffff"));
+    }
+
+    #[test]
+    fn diff_reports_size_changes() {
+        let small = Frame::new(Point2 { x: 2, y: 2 });
+        assert_eq!(
+            diff(&small, &sample()).unwrap(),
+            "frame size differs: expected 2x2, found 3x2"
+        );
+    }
+
+    #[test]
+    fn diff_truncates_long_reports() {
+        let expected = Frame::new(Point2 { x: 30, y: 1 });
+        let mut actual = expected.clone();
+        for x in 0..30 {
+            actual[(x, 0)].ch = 'x';
+        }
+        let diff = diff(&expected, &actual).unwrap();
+        assert!(diff.starts_with("30 cells differ"));
+        assert!(diff.ends_with("  ...and 10 more\n"));
+    }
+
+    // bless and compare in one test, since they depend on the environment
+    #[test]
+    fn assert_snapshot_blesses_and_compares() {
+        let dir = env::temp_dir().join(format!("tiler-snapshot-{}", std::process::id()));
+        let path = dir.join("nested").join("sample.snap");
+
+        env::set_var(BLESS_VAR, "1");
+        assert_snapshot(&sample(), &path);
+        env::remove_var(BLESS_VAR);
+        assert_eq!(fs::read_to_string(&path).unwrap(), sample().to_snapshot());
+
+        assert_snapshot(&sample(), &path);
+        let mut changed = sample();
+        changed[(0, 1)].ch = '!';
+        let result = std::panic::catch_unwind(|| assert_snapshot(&changed, &path));
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("does not match snapshot"));
+        assert!(message.contains("(0, 1): expected ' '"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
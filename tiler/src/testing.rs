@@ -0,0 +1,431 @@
+//! Helpers for testing `App` implementations without a window.
+//!
+//! A `Harness` owns the app and lets you feed it scripted input, then inspect the frame it draws.
+//! The `row` and `screen` functions do the same for a frame drawn some other way.
+use crate::{
+    backend::headless::Headless, snapshot, text::WIDE_CHAR_TAIL, App, Char, Frame, KeyCode,
+    MouseButton,
+};
+use mint::Point2;
+use std::{path::Path, time::Duration};
+
+/// A single step in an input script.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Input {
+    /// Press a key down.
+    KeyDown(KeyCode),
+    /// Release a key.
+    KeyUp(KeyCode),
+    /// Press and release a key.
+    Press(KeyCode),
+    /// Type a character of text.
+    Char(char),
+    /// Move the mouse over a cell.
+    MouseMove(Point2<usize>),
+    /// Press a mouse button wherever the mouse is.
+    MouseDown(MouseButton),
+    /// Release a mouse button wherever the mouse is.
+    MouseUp(MouseButton),
+    /// Move the mouse over a cell, then press and release a button.
+    Click(MouseButton, Point2<usize>),
+    /// Run one update of the app, drawing a new frame.
+    Tick,
+}
+
+/// Drives an `App` from scripted input, for use in tests.
+///
+/// This is a chainable wrapper around `Headless`, with assertions on the frame.
+pub struct Harness<A>
+where
+    A: App,
+{
+    headless: Headless<A>,
+}
+
+impl<A> Harness<A>
+where
+    A: App,
+{
+    /// Wrap the app and call `App::on_start`. Nothing is drawn until the first tick.
+    pub fn new(app: A) -> Self {
+        Harness {
+            headless: Headless::new(app),
+        }
+    }
+
+    /// Change the size of the grid, as if the window had been resized with
+    /// `GridMode::FitWindow`.
+    pub fn resize(&mut self, size: Point2<usize>) -> &mut Self {
+        self.headless.resize(size);
+        self
+    }
+
+    /// Press a key down.
+    ///
+    /// Modifiers are worked out from the modifier keys currently held, so to send shift+a use
+    /// `harness.key_down(KeyCode::LShift).press(KeyCode::A)`.
+    pub fn key_down(&mut self, keycode: KeyCode) -> &mut Self {
+        self.headless.key_down(keycode);
+        self
+    }
+
+    pub fn key_up(&mut self, keycode: KeyCode) -> &mut Self {
+        self.headless.key_up(keycode);
+        self
+    }
+
+    /// Press and release the key.
+    pub fn press(&mut self, keycode: KeyCode) -> &mut Self {
+        self.key_down(keycode).key_up(keycode)
+    }
+
+    /// Type the text, sending each character to `App::text_input`.
+    ///
+    /// No key events are sent, so use `press` as well if the app needs those.
+    pub fn type_text(&mut self, text: &str) -> &mut Self {
+        self.headless.type_text(text);
+        self
+    }
+
+    /// Move the mouse over `cell`.
+    pub fn mouse_move(&mut self, cell: Point2<usize>) -> &mut Self {
+        self.headless.mouse_move(Some(cell));
+        self
+    }
+
+    /// Move the mouse off the grid.
+    pub fn mouse_leave(&mut self) -> &mut Self {
+        self.headless.mouse_move(None);
+        self
+    }
+
+    pub fn mouse_down(&mut self, button: MouseButton) -> &mut Self {
+        self.headless.mouse_down(button);
+        self
+    }
+
+    pub fn mouse_up(&mut self, button: MouseButton) -> &mut Self {
+        self.headless.mouse_up(button);
+        self
+    }
+
+    /// Move the mouse over `cell`, then press and release the button.
+    pub fn click(&mut self, button: MouseButton, cell: Point2<usize>) -> &mut Self {
+        self.mouse_move(cell).mouse_down(button).mouse_up(button)
+    }
+
+    /// Scroll the mouse wheel by `delta` lines (positive `y` is up).
+    pub fn scroll(&mut self, delta: Point2<f32>) -> &mut Self {
+        self.headless.mouse_wheel(delta);
+        self
+    }
+
+    /// Clear the frame and call `App::update`, as if one tick had passed.
+    ///
+    /// The delta is the tick duration of the app's `UpdateMode`, or 1/60th of a second if it waits
+    /// for input.
+    pub fn tick(&mut self) -> &mut Self {
+        self.headless.update();
+        self
+    }
+
+    /// Clear the frame and call `App::update`, as if `delta` had passed since the last update.
+    pub fn advance(&mut self, delta: Duration) -> &mut Self {
+        self.headless.update_with(delta);
+        self
+    }
+
+    /// Call `tick` `n` times.
+    pub fn ticks(&mut self, n: usize) -> &mut Self {
+        for _ in 0..n {
+            self.tick();
+        }
+        self
+    }
+
+    /// Play each step of the script in order.
+    pub fn run_script(&mut self, script: &[Input]) -> &mut Self {
+        for input in script {
+            match *input {
+                Input::KeyDown(keycode) => self.key_down(keycode),
+                Input::KeyUp(keycode) => self.key_up(keycode),
+                Input::Press(keycode) => self.press(keycode),
+                Input::Char(ch) => self.type_text(ch.encode_utf8(&mut [0; 4])),
+                Input::MouseMove(cell) => self.mouse_move(cell),
+                Input::MouseDown(button) => self.mouse_down(button),
+                Input::MouseUp(button) => self.mouse_up(button),
+                Input::Click(button, cell) => self.click(button, cell),
+                Input::Tick => self.tick(),
+            };
+        }
+        self
+    }
+
+    /// Ask the app to close, as if the window's close button was pressed.
+    pub fn request_close(&mut self) -> &mut Self {
+        self.headless.request_close();
+        self
+    }
+
+    /// Tell the app the window gained or lost focus.
+    pub fn set_focused(&mut self, focused: bool) -> &mut Self {
+        self.headless.set_focused(focused);
+        self
+    }
+
+    /// Whether the app has asked to exit.
+    pub fn exited(&self) -> bool {
+        self.headless.exited()
+    }
+
+    /// The code the app asked to exit with, if it has.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.headless.exit_code()
+    }
+
+    /// Stop the app, calling `App::on_exit` (only the first time), and return its exit code.
+    pub fn finish(&mut self) -> i32 {
+        self.headless.finish()
+    }
+
+    pub fn app(&self) -> &A {
+        self.headless.app()
+    }
+
+    pub fn app_mut(&mut self) -> &mut A {
+        self.headless.app_mut()
+    }
+
+    /// The frame drawn by the last tick.
+    pub fn frame(&self) -> &Frame {
+        self.headless.frame()
+    }
+
+    /// The cell at `(x, y)` in the last frame.
+    pub fn cell(&self, x: usize, y: usize) -> &Char {
+        self.frame().get(Point2 { x, y })
+    }
+
+    /// The characters on row `y` of the last frame, see `row`.
+    pub fn row(&self, y: usize) -> String {
+        row(self.frame(), y)
+    }
+
+    /// The characters of the whole frame, see `screen`.
+    pub fn screen(&self) -> String {
+        screen(self.frame())
+    }
+
+    /// The position of the first occurrence of `needle` on the screen, searching row by row.
+    pub fn find(&self, needle: &str) -> Option<Point2<usize>> {
+        let needle: Vec<char> = needle.chars().collect();
+        if needle.is_empty() {
+            return None;
+        }
+        for y in 0..self.frame().size().y {
+            let cells = row_cells(self.frame(), y);
+            let found = cells
+                .windows(needle.len())
+                .find(|w| w.iter().map(|&(_, ch)| ch).eq(needle.iter().cloned()));
+            if let Some(&[(x, _), ..]) = found {
+                return Some(Point2 { x, y });
+            }
+        }
+        None
+    }
+
+    /// Panic unless the cell at `(x, y)` shows `ch`.
+    pub fn assert_char(&self, x: usize, y: usize, ch: char) {
+        let actual = self.cell(x, y).ch;
+        assert!(
+            actual == ch,
+            "expected {:?} at ({}, {}), found {:?}\n{}",
+            ch,
+            x,
+            y,
+            actual,
+            self.screen()
+        );
+    }
+
+    /// Panic unless the row `y` shows `s`, starting at column `x`.
+    pub fn assert_str(&self, x: usize, y: usize, s: &str) {
+        let actual: String = row_cells(self.frame(), y)
+            .into_iter()
+            .skip_while(|&(cell_x, _)| cell_x < x)
+            .map(|(_, ch)| ch)
+            .take(s.chars().count())
+            .collect();
+        assert!(
+            actual == s,
+            "expected {:?} at ({}, {}), found {:?}\n{}",
+            s,
+            x,
+            y,
+            actual,
+            self.screen()
+        );
+    }
+
+    /// Panic unless the screen matches `expected`.
+    ///
+    /// Trailing whitespace on each line and trailing blank lines are ignored, so the expected
+    /// screen only needs to contain what was actually drawn.
+    pub fn assert_screen(&self, expected: &str) {
+        let actual = normalize_screen(&self.screen());
+        let expected = normalize_screen(expected);
+        if actual == expected {
+            return;
+        }
+        let line = actual
+            .lines()
+            .zip(expected.lines())
+            .position(|(a, e)| a != e)
+            .unwrap_or_else(|| actual.lines().count().min(expected.lines().count()));
+        panic!(
+            "screen differs from line {}\n--- expected ---\n{}\n--- actual ---\n{}",
+            line, expected, actual
+        );
+    }
+
+    /// Compare the last frame against a golden file, see `snapshot::assert_snapshot`.
+    pub fn assert_snapshot(&self, path: impl AsRef<Path>) {
+        snapshot::assert_snapshot(self.frame(), path);
+    }
+}
+
+/// The characters on row `y` of `frame`. The second half of a wide character is skipped, so the
+/// string looks like what is on screen, but can be shorter than the frame is wide.
+pub fn row(frame: &Frame, y: usize) -> String {
+    row_cells(frame, y).into_iter().map(|(_, ch)| ch).collect()
+}
+
+/// The characters of the whole of `frame`, one line per row, see `row`.
+pub fn screen(frame: &Frame) -> String {
+    let rows: Vec<_> = (0..frame.size().y).map(|y| row(frame, y)).collect();
+    rows.join("\n")
+}
+
+/// The characters on row `y`, with the column each is in, skipping wide character tails.
+fn row_cells(frame: &Frame, y: usize) -> Vec<(usize, char)> {
+    (0..frame.size().x)
+        .map(|x| (x, frame.get(Point2 { x, y }).ch))
+        .filter(|&(_, ch)| ch != WIDE_CHAR_TAIL)
+        .collect()
+}
+
+fn normalize_screen(screen: &str) -> String {
+    let lines: Vec<&str> = screen.lines().map(|line| line.trim_end()).collect();
+    lines.join("\n").trim_end_matches('\n').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, Context};
+
+    /// Shows what was typed and where the mouse was last clicked, and exits with the number of
+    /// clicks on Escape.
+    #[derive(Default)]
+    struct Echo {
+        text: String,
+        clicked: Option<Point2<usize>>,
+        clicks: i32,
+    }
+
+    impl App for Echo {
+        const NAME: &'static str = "echo";
+        const SIZE: Point2<usize> = Point2 { x: 10, y: 3 };
+
+        fn update(&mut self, _ctx: Context<'_>, frame: &mut Frame) {
+            frame.print(
+                Point2 { x: 0, y: 0 },
+                &self.text,
+                Color::WHITE,
+                Color::BLACK,
+            );
+            if let Some(cell) = self.clicked {
+                frame[cell].ch = '*';
+            }
+        }
+
+        fn key_down_event(&mut self, mut ctx: Context<'_>, keycode: KeyCode, _: crate::Modifiers) {
+            match keycode {
+                KeyCode::Back => {
+                    self.text.pop();
+                }
+                KeyCode::Escape => ctx.exit_with(self.clicks),
+                _ => (),
+            }
+        }
+
+        fn text_input(&mut self, _ctx: Context<'_>, ch: char) {
+            self.text.push(ch);
+        }
+
+        fn mouse_down(&mut self, _ctx: Context<'_>, _: MouseButton, cell: Point2<usize>) {
+            self.clicked = Some(cell);
+            self.clicks += 1;
+        }
+    }
+
+    #[test]
+    fn nothing_is_drawn_before_the_first_tick() {
+        let mut harness = Harness::new(Echo::default());
+        harness.type_text("hi");
+        harness.assert_screen("");
+        harness.tick();
+        harness.assert_screen("hi");
+    }
+
+    #[test]
+    fn scripts_drive_the_app() {
+        let mut harness = Harness::new(Echo::default());
+        harness.run_script(&[
+            Input::Char('a'),
+            Input::Char('b'),
+            Input::Press(KeyCode::Back),
+            Input::Char('c'),
+            Input::Click(MouseButton::Left, Point2 { x: 2, y: 2 }),
+            Input::Tick,
+        ]);
+        harness.assert_screen("ac\n\n  *");
+        harness.assert_str(0, 0, "ac");
+        harness.assert_char(2, 2, '*');
+        assert_eq!(harness.find("c"), Some(Point2 { x: 1, y: 0 }));
+        assert_eq!(harness.find("x"), None);
+        assert_eq!(harness.row(2), "  *       ");
+    }
+
+    #[test]
+    fn exit_code_comes_from_the_app() {
+        let mut harness = Harness::new(Echo::default());
+        harness.click(MouseButton::Left, Point2 { x: 0, y: 0 });
+        harness.click(MouseButton::Left, Point2 { x: 1, y: 0 });
+        assert!(!harness.exited());
+        harness.press(KeyCode::Escape);
+        assert!(harness.exited());
+        assert_eq!(harness.exit_code(), Some(2));
+        assert_eq!(harness.finish(), 2);
+    }
+
+    #[test]
+    fn wide_characters_are_shown_once() {
+        let mut harness = Harness::new(Echo::default());
+        harness.type_text("漢字!").tick();
+        assert_eq!(harness.row(0), "漢字!     ");
+        assert!(!harness.screen().contains(WIDE_CHAR_TAIL));
+        harness.assert_screen("漢字!");
+        harness.assert_str(2, 0, "字!");
+        assert_eq!(harness.find("字!"), Some(Point2 { x: 2, y: 0 }));
+        assert_eq!(harness.cell(1, 0).ch, WIDE_CHAR_TAIL);
+    }
+
+    #[test]
+    #[should_panic(expected = "screen differs")]
+    fn assert_screen_reports_differences() {
+        let mut harness = Harness::new(Echo::default());
+        harness.type_text("hi").tick();
+        harness.assert_screen("ho");
+    }
+}
@@ -0,0 +1,382 @@
+//! Printing strings to a `Frame`.
+//!
+//! Text is measured in cells using the Unicode East Asian Width property: most characters take
+//! one cell, wide (e.g. CJK) characters take two, and zero-width characters (combining marks,
+//! joiners, ...) take none. Since a cell holds a single `char`, zero-width characters are dropped.
+use crate::{Char, Color, Frame, Rect};
+use mint::Point2;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// The character stored in the second cell of a wide character. Renderers draw nothing for it
+/// (apart from the background), since the wide character in the cell before covers it.
+pub const WIDE_CHAR_TAIL: char = '\0';
+
+/// How to line text up horizontally within a box.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// The number of cells `text` takes up on one line.
+pub fn width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// The number of cells `ch` takes up, or `None` for control characters.
+pub(crate) fn char_width(ch: char) -> Option<usize> {
+    if ch == WIDE_CHAR_TAIL {
+        return None;
+    }
+    ch.width()
+}
+
+/// A character along with its foreground and background colors.
+pub(crate) type StyledChar = (char, Color, Color);
+
+fn styled_width(text: &[StyledChar]) -> usize {
+    text.iter()
+        .map(|&(ch, _, _)| char_width(ch).unwrap_or(0))
+        .sum()
+}
+
+impl Frame {
+    /// Print `text` on one line starting at `pos`, cutting it off at the edge of the frame.
+    ///
+    /// Control characters (including newlines) are skipped. Returns the number of cells the
+    /// whole text would take up, whether or not it all fit.
+    pub fn print(&mut self, pos: Point2<usize>, text: &str, fg: Color, bg: Color) -> usize {
+        let clip = self.rect();
+        self.print_clipped(pos, text, fg, bg, clip)
+    }
+
+    /// Print `text` on one line starting at `pos`, only drawing the cells inside `clip` (and the
+    /// frame).
+    ///
+    /// A wide character that would be cut in half by the edge of the clip rectangle is drawn as a
+    /// space instead. Returns the number of cells the whole text would take up.
+    pub fn print_clipped(
+        &mut self,
+        pos: Point2<usize>,
+        text: &str,
+        fg: Color,
+        bg: Color,
+        clip: Rect,
+    ) -> usize {
+        self.print_styled(pos, text.chars().map(|ch| (ch, fg, bg)), clip)
+    }
+
+    /// Word-wrap `text` to the width of `rect` and print it there, one line per row starting at
+    /// the top. Newlines in the text start a new line.
+    ///
+    /// Lines that don't fit in `rect` are not drawn. Returns the number of lines the text wrapped
+    /// to, which is more than the height of `rect` if it was cut off.
+    pub fn print_wrapped(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        align: Align,
+        fg: Color,
+        bg: Color,
+    ) -> usize {
+        let text: Vec<_> = text.chars().map(|ch| (ch, fg, bg)).collect();
+        let clip = self.rect();
+        self.print_styled_wrapped(rect, &text, align, clip)
+    }
+
+    /// Print styled characters on one line, like `print_clipped`.
+    pub(crate) fn print_styled(
+        &mut self,
+        pos: Point2<usize>,
+        text: impl IntoIterator<Item = StyledChar>,
+        clip: Rect,
+    ) -> usize {
+        let clip = clip.intersection(self.rect());
+        let mut x = pos.x;
+        for (ch, fg, bg) in text {
+            let width = match char_width(ch) {
+                Some(width) if width > 0 => width,
+                _ => continue,
+            };
+            let cells = (x..x + width).map(|x| Point2 { x, y: pos.y });
+            let visible = cells.clone().filter(|&cell| clip.contains(cell)).count();
+            if visible == width {
+                self.put(Point2 { x, y: pos.y }, Char { ch, fg, bg });
+                if width == 2 {
+                    let tail = Char {
+                        ch: WIDE_CHAR_TAIL,
+                        fg,
+                        bg,
+                    };
+                    self.put(Point2 { x: x + 1, y: pos.y }, tail);
+                }
+            } else if visible > 0 {
+                for cell in cells.filter(|&cell| clip.contains(cell)) {
+                    self.put(cell, Char { ch: ' ', fg, bg });
+                }
+            }
+            x += width;
+        }
+        x - pos.x
+    }
+
+    /// Set a cell that is inside the frame, blanking the other half of any wide character it
+    /// used to be part of.
+    fn put(&mut self, pos: Point2<usize>, ch: Char) {
+        let old = self[pos].ch;
+        if old == WIDE_CHAR_TAIL && ch.ch != WIDE_CHAR_TAIL && pos.x > 0 {
+            self[(pos.x - 1, pos.y)].ch = ' ';
+        }
+        if old != WIDE_CHAR_TAIL {
+            if let Some(next) = self.try_get_mut((pos.x + 1, pos.y)) {
+                if next.ch == WIDE_CHAR_TAIL {
+                    next.ch = ' ';
+                }
+            }
+        }
+        self[pos] = ch;
+    }
+
+    /// Word-wrap and print styled characters, like `print_wrapped`, only drawing the cells inside
+    /// `clip`.
+    pub(crate) fn print_styled_wrapped(
+        &mut self,
+        rect: Rect,
+        text: &[StyledChar],
+        align: Align,
+        clip: Rect,
+    ) -> usize {
+        let clip = rect.intersection(clip);
+        let lines = wrap_styled(text, rect.width());
+        for (row, line) in lines.iter().enumerate().take(rect.height()) {
+            let line_width = styled_width(line);
+            let indent = match align {
+                Align::Left => 0,
+                Align::Center => (rect.width().saturating_sub(line_width)) / 2,
+                Align::Right => rect.width().saturating_sub(line_width),
+            };
+            let pos = Point2 {
+                x: rect.top_left.x + indent,
+                y: rect.top_left.y + row,
+            };
+            self.print_styled(pos, line.iter().cloned(), clip);
+        }
+        lines.len()
+    }
+}
+
+/// Split `text` into lines no wider than `max_width` cells, breaking between words where
+/// possible.
+///
+/// Runs of spaces between words are collapsed, and words wider than a whole line are broken
+/// between characters.
+pub fn wrap(text: &str, max_width: usize) -> Vec<String> {
+    let text: Vec<_> = text
+        .chars()
+        .map(|ch| (ch, Color::TRANSPARENT, Color::TRANSPARENT))
+        .collect();
+    wrap_styled(&text, max_width)
+        .into_iter()
+        .map(|line| line.into_iter().map(|(ch, _, _)| ch).collect())
+        .collect()
+}
+
+/// Word-wrap styled characters, like `wrap`. The space between two words on a line gets the style
+/// of the whitespace that was there in `text`.
+pub(crate) fn wrap_styled(text: &[StyledChar], max_width: usize) -> Vec<Vec<StyledChar>> {
+    let max_width = max_width.max(1);
+    let is_space = |&(ch, _, _): &StyledChar| ch.is_whitespace();
+    let mut lines = Vec::new();
+    for paragraph in text.split(|&(ch, _, _)| ch == '\n') {
+        let mut line = Vec::new();
+        let mut line_width = 0;
+        let mut rest = paragraph;
+        while let Some(start) = rest.iter().position(|ch| !is_space(ch)) {
+            let (_, space_fg, space_bg) = rest[start.saturating_sub(1)];
+            let len = rest[start..]
+                .iter()
+                .position(is_space)
+                .unwrap_or(rest.len() - start);
+            let word = &rest[start..start + len];
+            rest = &rest[start + len..];
+
+            let word_width = styled_width(word);
+            let space = if line.is_empty() { 0 } else { 1 };
+            if line_width + space + word_width <= max_width {
+                if space == 1 {
+                    line.push((' ', space_fg, space_bg));
+                }
+                line.extend_from_slice(word);
+                line_width += space + word_width;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(line);
+                line = Vec::new();
+                line_width = 0;
+            }
+            // the word might still be too long for a line on its own
+            for &styled in word {
+                let ch_width = char_width(styled.0).unwrap_or(0);
+                if line_width + ch_width > max_width && !line.is_empty() {
+                    lines.push(line);
+                    line = Vec::new();
+                    line_width = 0;
+                }
+                line.push(styled);
+                line_width += ch_width;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::screen;
+
+    fn printed(width: usize, x: usize, text: &str) -> (String, usize) {
+        let mut frame = Frame::new(Point2 { x: width, y: 1 });
+        let len = frame.print(Point2 { x, y: 0 }, text, Color::WHITE, Color::BLACK);
+        assert!(!has_tails_alone(&frame), "{:?}", text);
+        (screen(&frame), len)
+    }
+
+    /// Whether any wide character has lost its head or tail.
+    fn has_tails_alone(frame: &Frame) -> bool {
+        let size = frame.size();
+        (0..size.y).any(|y| {
+            (0..size.x).any(|x| {
+                let is_tail =
+                    |x: usize| frame.try_get((x, y)).map(|c| c.ch) == Some(WIDE_CHAR_TAIL);
+                let is_wide = |x: usize| frame[(x, y)].ch.width() == Some(2);
+                is_tail(x) != (x > 0 && is_wide(x - 1)) || (is_wide(x) && !is_tail(x + 1))
+            })
+        })
+    }
+
+    #[test]
+    fn wrapping() {
+        let tests: &[(&str, usize, &[&str])] = &[
+            ("hello world", 11, &["hello world"]),
+            ("hello world", 10, &["hello", "world"]),
+            ("hello world", 5, &["hello", "world"]),
+            ("a bb ccc", 4, &["a bb", "ccc"]),
+            ("a   b  ", 10, &["a b"]),
+            ("a\n\nb", 5, &["a", "", "b"]),
+            ("", 5, &[""]),
+            // words too long for a line are broken up
+            ("abcdefgh", 3, &["abc", "def", "gh"]),
+            ("hi abcdefgh x", 4, &["hi", "abcd", "efgh", "x"]),
+            ("abc", 0, &["a", "b", "c"]),
+            // wide characters aren't split across lines
+            ("漢字漢字", 5, &["漢字", "漢字"]),
+            ("a漢字", 2, &["a", "漢", "字"]),
+            // zero-width characters don't count
+            ("e\u{301}e\u{301} x", 3, &["e\u{301}e\u{301}", "x"]),
+        ];
+        for &(text, width, expected) in tests {
+            assert_eq!(wrap(text, width), expected, "{:?} at {}", text, width);
+        }
+    }
+
+    #[test]
+    fn printing() {
+        assert_eq!(printed(6, 1, "abc"), (" abc  ".to_string(), 3));
+        assert_eq!(printed(4, 2, "abcdef"), ("  ab".to_string(), 6));
+        assert_eq!(printed(4, 5, "abc"), ("    ".to_string(), 3));
+        // control characters are skipped, and so are zero-width ones as a cell holds one char
+        assert_eq!(printed(4, 0, "a\tb\n"), ("ab  ".to_string(), 2));
+        assert_eq!(printed(4, 0, "e\u{301}x\u{200b}"), ("ex  ".to_string(), 2));
+    }
+
+    #[test]
+    fn wide_characters() {
+        assert_eq!(printed(5, 0, "漢字!"), ("漢字!".to_string(), 5));
+        // cut in half by the edge of the frame, so drawn as a space
+        assert_eq!(printed(4, 1, "ab漢"), (" ab ".to_string(), 4));
+        assert_eq!(printed(3, 0, "a漢字"), ("a漢".to_string(), 5));
+
+        // and by the edge of the clip rectangle
+        let mut frame = Frame::new(Point2 { x: 5, y: 1 });
+        frame.print(Point2 { x: 0, y: 0 }, "xxxxx", Color::WHITE, Color::BLACK);
+        let clip = Rect::from_parts(1, 0, 4, 1);
+        let len = frame.print_clipped(
+            Point2 { x: 0, y: 0 },
+            "漢a漢",
+            Color::RED,
+            Color::BLACK,
+            clip,
+        );
+        assert_eq!(len, 5);
+        assert_eq!(screen(&frame), "x a x");
+        assert_eq!(frame[(1, 0)].fg, Color::RED);
+        assert!(!has_tails_alone(&frame));
+    }
+
+    #[test]
+    fn printing_over_half_a_wide_character_removes_the_rest() {
+        let mut frame = Frame::new(Point2 { x: 6, y: 1 });
+        frame.print(Point2 { x: 0, y: 0 }, "漢字漢", Color::WHITE, Color::BLACK);
+        frame.print(Point2 { x: 1, y: 0 }, "a", Color::WHITE, Color::BLACK);
+        assert_eq!(screen(&frame), " a字漢");
+        frame.print(Point2 { x: 2, y: 0 }, "b", Color::WHITE, Color::BLACK);
+        assert_eq!(screen(&frame), " ab 漢");
+        assert!(!has_tails_alone(&frame));
+        // a wide character over the tail of another
+        frame.print(Point2 { x: 5, y: 0 }, "c", Color::WHITE, Color::BLACK);
+        frame.print(Point2 { x: 3, y: 0 }, "字", Color::WHITE, Color::BLACK);
+        assert_eq!(screen(&frame), " ab字c");
+        frame.print(Point2 { x: 2, y: 0 }, "漢", Color::WHITE, Color::BLACK);
+        assert_eq!(screen(&frame), " a漢 c");
+        assert!(!has_tails_alone(&frame));
+    }
+
+    #[test]
+    fn alignment() {
+        let tests = [
+            (Align::Left, "ab cd \nefgh  "),
+            (Align::Center, "ab cd \n efgh "),
+            (Align::Right, " ab cd\n  efgh"),
+        ];
+        for &(align, expected) in &tests {
+            let mut frame = Frame::new(Point2 { x: 8, y: 3 });
+            let rect = Rect::from_parts(1, 1, 7, 3);
+            let lines = frame.print_wrapped(rect, "ab cd efgh", align, Color::WHITE, Color::BLACK);
+            assert_eq!(lines, 2);
+            let expected: Vec<String> =
+                expected.lines().map(|line| format!(" {} ", line)).collect();
+            assert_eq!(
+                screen(&frame),
+                format!("        \n{}", expected.join("\n")),
+                "{:?}",
+                align
+            );
+        }
+    }
+
+    #[test]
+    fn wrapped_text_is_cut_off_at_the_bottom() {
+        let mut frame = Frame::new(Point2 { x: 5, y: 2 });
+        let rect = Rect::from_parts(0, 0, 5, 1);
+        let lines = frame.print_wrapped(
+            rect,
+            "one two three",
+            Align::Left,
+            Color::WHITE,
+            Color::BLACK,
+        );
+        assert_eq!(lines, 3);
+        assert_eq!(screen(&frame), "one  \n     ");
+        // lines wider than the rect (only possible with wide characters) are clipped to it
+        let mut frame = Frame::new(Point2 { x: 4, y: 1 });
+        let rect = Rect::from_parts(0, 0, 1, 1);
+        let lines = frame.print_wrapped(rect, "漢", Align::Right, Color::WHITE, Color::BLACK);
+        assert_eq!(lines, 1);
+        assert_eq!(screen(&frame), "    ");
+    }
+}
@@ -0,0 +1,37 @@
+//! Deciding when the app should be updated.
+use std::time::Duration;
+
+/// The delta used when updates are driven by hand (e.g. in tests) and there is no fixed tick rate.
+pub(crate) const DEFAULT_TICK: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// The most ticks to run at once when catching up. If we fall further behind than this (e.g.
+/// because the machine was suspended) the missed ticks are dropped.
+pub(crate) const MAX_CATCH_UP_TICKS: u32 = 5;
+
+/// How often `App::update` is called.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UpdateMode {
+    /// Update a fixed number of times per second, whether or not there is any input.
+    Fixed(u32),
+    /// Only update (and redraw) after input, or when the app asks for it with
+    /// `Context::request_update`. This suits turn-based games, and uses no CPU while idle.
+    WaitForInput,
+}
+
+impl UpdateMode {
+    /// The time between updates, or `None` if they aren't at a fixed rate.
+    pub fn tick_duration(self) -> Option<Duration> {
+        match self {
+            UpdateMode::Fixed(ticks_per_second) => {
+                Some(Duration::from_secs(1) / ticks_per_second.max(1))
+            }
+            UpdateMode::WaitForInput => None,
+        }
+    }
+}
+
+impl Default for UpdateMode {
+    fn default() -> Self {
+        UpdateMode::Fixed(60)
+    }
+}
@@ -0,0 +1,285 @@
+//! Views of part of a `Frame`, with their own coordinates.
+use crate::{text::StyledChar, Align, CellPos, Char, Color, Frame, Rect};
+use mint::Point2;
+use std::ops::{Index, IndexMut};
+
+/// What cells outside a view read as.
+const BLANK: Char = Char {
+    ch: ' ',
+    fg: Color::TRANSPARENT,
+    bg: Color::TRANSPARENT,
+};
+
+/// A rectangular region of a `Frame`, borrowed from it with `Frame::view`.
+///
+/// Cell `(0, 0)` of the view is the top-left of its rectangle. Writes outside the view (or outside
+/// the frame, if the rectangle runs off its edge) are silently dropped, and reads there give a
+/// blank cell, so drawing code doesn't need to bounds-check.
+#[derive(Debug)]
+pub struct FrameView<'a> {
+    frame: &'a mut Frame,
+    /// Where the view's `(0, 0)` is in the frame.
+    origin: Point2<usize>,
+    size: Point2<usize>,
+    /// The cells of the frame that can be drawn to, in frame coordinates.
+    clip: Rect,
+    /// Stands in for cells outside the clip rectangle, so `IndexMut` has something to return.
+    scratch: Char,
+}
+
+impl Frame {
+    /// A view of the cells in `rect`. The rectangle may run off the edge of the frame.
+    pub fn view(&mut self, rect: Rect) -> FrameView<'_> {
+        let clip = rect.intersection(self.rect());
+        FrameView {
+            frame: self,
+            origin: rect.top_left,
+            size: rect.size(),
+            clip,
+            scratch: BLANK,
+        }
+    }
+}
+
+impl<'a> FrameView<'a> {
+    /// The size of the view, in cells. This includes any part of it that is off the frame.
+    #[inline]
+    pub fn size(&self) -> Point2<usize> {
+        self.size
+    }
+
+    /// The rectangle covering the whole view, in its own coordinates.
+    pub fn rect(&self) -> Rect {
+        Rect::from_size(Point2 { x: 0, y: 0 }, self.size)
+    }
+
+    /// A view of the cells in `rect` (in this view's coordinates), clipped to this view.
+    pub fn view(&mut self, rect: Rect) -> FrameView<'_> {
+        let origin = self.to_frame(rect.top_left);
+        let clip = Rect::from_size(origin, rect.size()).intersection(self.clip);
+        FrameView {
+            frame: &mut *self.frame,
+            origin,
+            size: rect.size(),
+            clip,
+            scratch: BLANK,
+        }
+    }
+
+    /// Get a cell by location, or a blank cell if it can't be seen.
+    #[inline]
+    pub fn get(&self, pos: Point2<usize>) -> &Char {
+        match self.visible(pos) {
+            Some(pos) => self.frame.get(pos),
+            None => &BLANK,
+        }
+    }
+
+    /// Get a mutable ref to a cell by location. If the cell can't be seen, anything written
+    /// through the ref is thrown away.
+    #[inline]
+    pub fn get_mut(&mut self, pos: Point2<usize>) -> &mut Char {
+        match self.visible(pos) {
+            Some(pos) => self.frame.get_mut(pos),
+            None => {
+                self.scratch = BLANK;
+                &mut self.scratch
+            }
+        }
+    }
+
+    /// Set a cell, returning whether it can be seen. Positions may be signed, like with
+    /// `Frame::set`.
+    pub fn set(&mut self, pos: impl CellPos, ch: Char) -> bool {
+        match pos.to_cell().and_then(|pos| self.visible(pos)) {
+            Some(pos) => {
+                self.frame[pos] = ch;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set every visible cell to blank.
+    pub fn clear(&mut self) {
+        self.fill(Char::default());
+    }
+
+    /// Set every visible cell to `ch`.
+    pub fn fill(&mut self, ch: Char) {
+        for x in self.clip.top_left.x..self.clip.bottom_right.x {
+            for y in self.clip.top_left.y..self.clip.bottom_right.y {
+                self.frame[(x, y)] = ch;
+            }
+        }
+    }
+
+    /// Like `Frame::print`, cutting the text off at the edge of the view.
+    pub fn print(&mut self, pos: Point2<usize>, text: &str, fg: Color, bg: Color) -> usize {
+        let pos = self.to_frame(pos);
+        let text = text.chars().map(|ch| (ch, fg, bg));
+        self.frame.print_styled(pos, text, self.clip)
+    }
+
+    /// Like `Frame::print_wrapped`, with `rect` in the view's coordinates.
+    pub fn print_wrapped(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        align: Align,
+        fg: Color,
+        bg: Color,
+    ) -> usize {
+        let rect = Rect::from_size(self.to_frame(rect.top_left), rect.size());
+        let text: Vec<StyledChar> = text.chars().map(|ch| (ch, fg, bg)).collect();
+        self.frame
+            .print_styled_wrapped(rect, &text, align, self.clip)
+    }
+
+    /// The frame coordinates of a position in the view.
+    fn to_frame(&self, pos: Point2<usize>) -> Point2<usize> {
+        Point2 {
+            x: self.origin.x + pos.x,
+            y: self.origin.y + pos.y,
+        }
+    }
+
+    /// The frame coordinates of a position in the view, if it can be seen.
+    fn visible(&self, pos: Point2<usize>) -> Option<Point2<usize>> {
+        if pos.x >= self.size.x || pos.y >= self.size.y {
+            return None;
+        }
+        let pos = self.to_frame(pos);
+        if self.clip.contains(pos) {
+            Some(pos)
+        } else {
+            None
+        }
+    }
+}
+
+impl Index<(usize, usize)> for FrameView<'_> {
+    type Output = Char;
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        self.get(Point2 { x, y })
+    }
+}
+
+impl IndexMut<(usize, usize)> for FrameView<'_> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        self.get_mut(Point2 { x, y })
+    }
+}
+
+impl Index<Point2<usize>> for FrameView<'_> {
+    type Output = Char;
+    fn index(&self, pos: Point2<usize>) -> &Self::Output {
+        self.get(pos)
+    }
+}
+
+impl IndexMut<Point2<usize>> for FrameView<'_> {
+    fn index_mut(&mut self, pos: Point2<usize>) -> &mut Self::Output {
+        self.get_mut(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::screen;
+
+    fn tile(ch: char) -> Char {
+        Char {
+            ch,
+            ..Char::default()
+        }
+    }
+
+    /// A frame of the given size, full of dots.
+    fn dots(width: usize, height: usize) -> Frame {
+        let mut frame = Frame::new(Point2 {
+            x: width,
+            y: height,
+        });
+        frame.view(frame.rect()).fill(tile('.'));
+        frame
+    }
+
+    #[test]
+    fn writes_outside_the_view_are_dropped() {
+        let mut frame = dots(5, 4);
+        let mut view = frame.view(Rect::from_parts(1, 1, 4, 3));
+        assert_eq!(view.size(), Point2 { x: 3, y: 2 });
+        view[(0, 0)] = tile('a');
+        view[(2, 1)] = tile('b');
+        view[(3, 0)] = tile('!');
+        view[(0, 2)] = tile('!');
+        view[Point2 { x: 9, y: 9 }].ch = '!';
+        assert!(view.set((1, 1), tile('c')));
+        assert!(!view.set((-1, 0), tile('!')));
+        assert!(!view.set((0, -1), tile('!')));
+        assert!(!view.set((3, 1), tile('!')));
+        // reads outside give a blank cell, even after a write there
+        assert_eq!(view[(3, 0)], BLANK);
+        assert_eq!(view[(2, 1)].ch, 'b');
+        assert_eq!(screen(&frame), ".....\n.a...\n..cb.\n.....");
+
+        let mut view = frame.view(Rect::from_parts(1, 1, 4, 3));
+        view.print(Point2 { x: 1, y: 0 }, "xyz", Color::WHITE, Color::BLACK);
+        view.print(Point2 { x: 2, y: 1 }, "漢", Color::WHITE, Color::BLACK);
+        assert_eq!(screen(&frame), ".....\n.axy.\n..c .\n.....");
+        frame.view(Rect::from_parts(1, 1, 4, 3)).clear();
+        assert_eq!(screen(&frame), ".....\n.   .\n.   .\n.....");
+    }
+
+    #[test]
+    fn nested_views_add_their_offsets() {
+        let mut frame = dots(6, 5);
+        let mut outer = frame.view(Rect::from_parts(1, 1, 6, 5));
+        let mut middle = outer.view(Rect::from_parts(1, 1, 4, 4));
+        assert_eq!(middle.rect(), Rect::from_parts(0, 0, 3, 3));
+        middle[(0, 0)] = tile('m');
+        let mut inner = middle.view(Rect::from_parts(1, 1, 3, 2));
+        inner[(0, 0)] = tile('i');
+        inner[(1, 0)] = tile('j');
+        inner[(0, 1)] = tile('!');
+        assert_eq!(screen(&frame), "......\n......\n..m...\n...ij.\n......");
+
+        // a nested view running off its parent is clipped to it
+        let mut frame = dots(6, 5);
+        let mut outer = frame.view(Rect::from_parts(1, 1, 4, 3));
+        let mut inner = outer.view(Rect::from_parts(2, 1, 6, 4));
+        assert_eq!(inner.size(), Point2 { x: 4, y: 3 });
+        inner.fill(tile('x'));
+        inner.print(Point2 { x: 0, y: 0 }, "yz", Color::WHITE, Color::BLACK);
+        assert!(!inner.set((0, 1), tile('!')));
+        assert_eq!(screen(&frame), "......\n......\n...y..\n......\n......");
+    }
+
+    #[test]
+    fn views_running_off_the_frame() {
+        let mut frame = dots(4, 3);
+        let mut view = frame.view(Rect::from_parts(2, 1, 7, 5));
+        assert_eq!(view.size(), Point2 { x: 5, y: 4 });
+        assert_eq!(view[(4, 3)], BLANK);
+        view[(4, 3)] = tile('!');
+        assert!(!view.set((2, 0), tile('!')));
+        assert!(view.set((1, 1), tile('a')));
+        view.print_wrapped(
+            Rect::from_parts(0, 0, 5, 4),
+            "bc de",
+            Align::Left,
+            Color::WHITE,
+            Color::BLACK,
+        );
+        assert_eq!(screen(&frame), "....\n..bc\n...a");
+
+        // entirely off the frame
+        let mut view = frame.view(Rect::from_parts(5, 5, 8, 8));
+        view.fill(tile('!'));
+        view[(0, 0)] = tile('!');
+        assert_eq!(view[(0, 0)], BLANK);
+        assert!(!screen(&frame).contains('!'));
+    }
+}
@@ -0,0 +1,1137 @@
+//! A small set of widgets for menus, lists and dialogs.
+//!
+//! Widgets keep their own state (the selected item, the text typed so far, ...) and are drawn
+//! into a rectangle of the frame each update. The app passes key events to the focused widget,
+//! and looks at the `Response` to see if something happened:
+//!
+//! ```ignore
+//! fn key_down_event(&mut self, ctx: Context, key: KeyCode, modifiers: Modifiers) {
+//!     if self.focus.key_down(key, modifiers).used() {
+//!         return;
+//!     }
+//!     let widget: &mut dyn Widget = match self.focus.current() {
+//!         0 => &mut self.inventory,
+//!         _ => &mut self.drop_button,
+//!     };
+//!     if widget.key_down(key, modifiers) == Response::Activated {
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! where `self.focus` was made with `Focus::new(&[&self.inventory, &self.drop_button])`. Text
+//! typed into a `TextInput` comes from `App::text_input`, and is passed on the same way.
+//!
+//! A `Dialog` is modal: while one is open, send it every key event and draw it last.
+use crate::{
+    text::{self, char_width},
+    Align, Border, BorderStyle, Char, Color, Frame, KeyCode, Modifiers, Rect,
+};
+use mint::Point2;
+
+/// The colors and border style widgets are drawn with.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Theme {
+    /// Normal text.
+    pub fg: Color,
+    /// Behind normal text.
+    pub bg: Color,
+    /// Behind dialogs.
+    pub panel: Color,
+    /// The focused widget or item.
+    pub focus_fg: Color,
+    pub focus_bg: Color,
+    /// Behind the selected item of a list that doesn't have focus.
+    pub selected_bg: Color,
+    /// Borders, scrollbar thumbs and the filled part of progress bars.
+    pub accent: Color,
+    /// Scrollbar tracks, the empty part of progress bars and text input fields.
+    pub track: Color,
+    pub border: BorderStyle,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            fg: Color::LIGHT_GRAY,
+            bg: Color::TRANSPARENT,
+            panel: Color::BLACK,
+            focus_fg: Color::BLACK,
+            focus_bg: Color::LIGHT_GRAY,
+            selected_bg: Color::DIM_GRAY,
+            accent: Color::STEEL_BLUE,
+            track: Color::rgb(40, 40, 40),
+            border: BorderStyle::SINGLE,
+        }
+    }
+}
+
+/// What a widget did with an event.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Response {
+    /// The widget didn't use the event, so the app can handle it.
+    Ignored,
+    /// The widget used the event to change its state.
+    Handled,
+    /// The user chose something: pressed a button, picked a list item, submitted text or
+    /// picked a dialog button.
+    Activated,
+    /// The user backed out of a dialog.
+    Cancelled,
+}
+
+impl Response {
+    /// Whether the widget used the event.
+    pub fn used(self) -> bool {
+        self != Response::Ignored
+    }
+}
+
+/// Something that can be drawn in a rectangle and take keyboard input.
+#[allow(unused_variables)]
+pub trait Widget {
+    /// Draw the widget in `rect`. `focused` is whether it has keyboard focus.
+    fn draw(&mut self, frame: &mut Frame, rect: Rect, theme: &Theme, focused: bool);
+
+    /// Handle a key press while the widget has focus.
+    fn key_down(&mut self, key: KeyCode, modifiers: Modifiers) -> Response {
+        Response::Ignored
+    }
+
+    /// Handle typed text while the widget has focus.
+    fn text_input(&mut self, ch: char) -> Response {
+        Response::Ignored
+    }
+
+    /// Whether the widget can have keyboard focus.
+    fn focusable(&self) -> bool {
+        true
+    }
+}
+
+/// Which of a number of widgets has keyboard focus. Tab moves to the next one and shift-Tab to
+/// the previous one, wrapping around and skipping widgets that can't have focus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Focus {
+    /// Whether each widget can have focus.
+    focusable: Vec<bool>,
+    current: usize,
+}
+
+impl Focus {
+    /// Focus for `widgets`, in tab order, starting with the first one that can have focus.
+    pub fn new(widgets: &[&dyn Widget]) -> Self {
+        let mut focus = Focus {
+            focusable: widgets.iter().map(|widget| widget.focusable()).collect(),
+            current: 0,
+        };
+        if !focus.can_focus(0) {
+            focus.next();
+        }
+        focus
+    }
+
+    fn can_focus(&self, index: usize) -> bool {
+        self.focusable.get(index).cloned().unwrap_or(false)
+    }
+
+    /// Change whether the widget at `index` can have focus, e.g. to disable a button. If it had
+    /// focus, focus moves on to the next widget.
+    pub fn set_focusable(&mut self, index: usize, focusable: bool) {
+        if let Some(f) = self.focusable.get_mut(index) {
+            *f = focusable;
+            if !focusable && self.current == index {
+                self.next();
+            }
+        }
+    }
+
+    /// The index of the focused widget.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Whether the widget at `index` has focus.
+    pub fn is(&self, index: usize) -> bool {
+        self.current == index
+    }
+
+    /// Focus the widget at `index`, if there is one and it can have focus.
+    pub fn set(&mut self, index: usize) {
+        if self.can_focus(index) {
+            self.current = index;
+        }
+    }
+
+    /// Move focus to the next widget that can have focus. If there aren't any, it stays put.
+    pub fn next(&mut self) {
+        let len = self.focusable.len();
+        if let Some(index) = (1..=len)
+            .map(|step| (self.current + step) % len)
+            .find(|&index| self.can_focus(index))
+        {
+            self.current = index;
+        }
+    }
+
+    /// Move focus to the previous widget that can have focus.
+    pub fn prev(&mut self) {
+        let len = self.focusable.len();
+        if let Some(index) = (1..=len)
+            .map(|step| (self.current + len - step) % len)
+            .find(|&index| self.can_focus(index))
+        {
+            self.current = index;
+        }
+    }
+
+    /// Move focus on Tab or shift-Tab.
+    pub fn key_down(&mut self, key: KeyCode, modifiers: Modifiers) -> Response {
+        match key {
+            KeyCode::Tab if modifiers.shift => self.prev(),
+            KeyCode::Tab => self.next(),
+            _ => return Response::Ignored,
+        }
+        Response::Handled
+    }
+}
+
+/// Text, word-wrapped to its rectangle. Labels can't have focus.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub text: String,
+    pub align: Align,
+}
+
+impl Label {
+    pub fn new(text: impl Into<String>) -> Self {
+        Label {
+            text: text.into(),
+            align: Align::Left,
+        }
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+impl Widget for Label {
+    fn draw(&mut self, frame: &mut Frame, rect: Rect, theme: &Theme, _focused: bool) {
+        frame.print_wrapped(rect, &self.text, self.align, theme.fg, theme.bg);
+    }
+
+    fn focusable(&self) -> bool {
+        false
+    }
+}
+
+/// A button, drawn as `[ label ]` on the top row of its rectangle. Return or space presses it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Button {
+    pub label: String,
+}
+
+impl Button {
+    pub fn new(label: impl Into<String>) -> Self {
+        Button {
+            label: label.into(),
+        }
+    }
+
+    /// The number of cells the button takes up.
+    pub fn width(&self) -> usize {
+        button_width(&self.label)
+    }
+}
+
+impl Widget for Button {
+    fn draw(&mut self, frame: &mut Frame, rect: Rect, theme: &Theme, focused: bool) {
+        let row = Rect::from_size(
+            rect.top_left,
+            Point2 {
+                x: rect.width(),
+                y: rect.height().min(1),
+            },
+        );
+        frame.print_wrapped(
+            row,
+            &format!("[ {} ]", self.label),
+            Align::Center,
+            if focused { theme.focus_fg } else { theme.fg },
+            if focused { theme.focus_bg } else { theme.bg },
+        );
+    }
+
+    fn key_down(&mut self, key: KeyCode, _modifiers: Modifiers) -> Response {
+        match key {
+            KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Space => Response::Activated,
+            _ => Response::Ignored,
+        }
+    }
+}
+
+fn button_width(label: &str) -> usize {
+    text::width(label) + 4
+}
+
+/// A list of items, one per row, with one selected. It scrolls to keep the selection in view,
+/// with a scrollbar when the items don't all fit.
+///
+/// The arrow keys, page up/down, home and end move the selection, and return picks it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct List {
+    pub items: Vec<String>,
+    selected: usize,
+    /// The index of the top item shown.
+    scroll: usize,
+    /// The number of rows last drawn, for paging.
+    page: usize,
+}
+
+impl List {
+    pub fn new(items: Vec<String>) -> Self {
+        List {
+            items,
+            ..List::default()
+        }
+    }
+
+    /// The index of the selected item. This is 0 if the list is empty.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// The selected item, if the list isn't empty.
+    pub fn selected_item(&self) -> Option<&str> {
+        self.items.get(self.selected).map(|item| item.as_str())
+    }
+
+    /// Select the item at `index`, or the last one if it is past the end.
+    pub fn select(&mut self, index: usize) {
+        self.selected = index.min(self.items.len().saturating_sub(1));
+    }
+}
+
+impl Widget for List {
+    fn draw(&mut self, frame: &mut Frame, rect: Rect, theme: &Theme, focused: bool) {
+        if rect.is_empty() {
+            return;
+        }
+        let height = rect.height();
+        self.page = height;
+        self.select(self.selected);
+        // scroll so the selection is visible
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + height {
+            self.scroll = self.selected + 1 - height;
+        }
+        self.scroll = self.scroll.min(self.items.len().saturating_sub(height));
+
+        let overflow = self.items.len() > height;
+        let text_width = if overflow {
+            rect.width().saturating_sub(1)
+        } else {
+            rect.width()
+        };
+        let mut view = frame.view(rect);
+        for (row, index) in (self.scroll..self.items.len()).take(height).enumerate() {
+            let (fg, bg) = if index != self.selected {
+                (theme.fg, theme.bg)
+            } else if focused {
+                (theme.focus_fg, theme.focus_bg)
+            } else {
+                (theme.fg, theme.selected_bg)
+            };
+            let mut line = view.view(Rect::from_parts(0, row, text_width, row + 1));
+            line.fill(Char { ch: ' ', fg, bg });
+            line.print(Point2 { x: 0, y: 0 }, &self.items[index], fg, bg);
+        }
+
+        if overflow {
+            let len = self.items.len();
+            let thumb = (height * height / len).max(1);
+            let thumb_top = self.scroll * (height - thumb) / (len - height);
+            let x = rect.width() - 1;
+            for y in 0..height {
+                let on_thumb = y >= thumb_top && y < thumb_top + thumb;
+                view[(x, y)] = Char {
+                    ch: if on_thumb { '█' } else { '│' },
+                    fg: if on_thumb { theme.accent } else { theme.track },
+                    bg: theme.bg,
+                };
+            }
+        }
+    }
+
+    fn key_down(&mut self, key: KeyCode, _modifiers: Modifiers) -> Response {
+        if self.items.is_empty() {
+            return Response::Ignored;
+        }
+        let page = self.page.max(1);
+        let selected = match key {
+            KeyCode::Up => self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected + 1,
+            KeyCode::PageUp => self.selected.saturating_sub(page),
+            KeyCode::PageDown => self.selected + page,
+            KeyCode::Home => 0,
+            KeyCode::End => self.items.len(),
+            KeyCode::Return | KeyCode::NumpadEnter => return Response::Activated,
+            _ => return Response::Ignored,
+        };
+        self.select(selected);
+        Response::Handled
+    }
+}
+
+/// A scrolling log of messages, newest at the bottom. Long messages are word-wrapped.
+///
+/// Page up/down and the arrow keys scroll back through old messages, and end jumps back to the
+/// newest. Logs can't have focus, but can be sent keys anyway.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageLog {
+    messages: Vec<(String, Color)>,
+    /// The most messages to keep.
+    pub max_messages: usize,
+    /// How many lines up from the bottom the log is scrolled.
+    scroll: usize,
+    /// The furthest it could be scrolled when last drawn, with the top line at the top.
+    max_scroll: usize,
+    page: usize,
+}
+
+impl Default for MessageLog {
+    fn default() -> Self {
+        MessageLog {
+            messages: Vec::new(),
+            max_messages: 100,
+            scroll: 0,
+            max_scroll: 0,
+            page: 0,
+        }
+    }
+}
+
+impl MessageLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a message at the bottom, in the given color.
+    pub fn push(&mut self, message: impl Into<String>, fg: Color) {
+        self.messages.push((message.into(), fg));
+        if self.messages.len() > self.max_messages {
+            let extra = self.messages.len() - self.max_messages;
+            self.messages.drain(..extra);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+        self.scroll = 0;
+        self.max_scroll = 0;
+    }
+}
+
+impl Widget for MessageLog {
+    fn draw(&mut self, frame: &mut Frame, rect: Rect, theme: &Theme, _focused: bool) {
+        let height = rect.height();
+        self.page = height;
+        let lines: Vec<(String, Color)> = self
+            .messages
+            .iter()
+            .flat_map(|(message, fg)| {
+                text::wrap(message, rect.width())
+                    .into_iter()
+                    .map(move |line| (line, *fg))
+            })
+            .collect();
+        self.max_scroll = lines.len().saturating_sub(height);
+        self.scroll = self.scroll.min(self.max_scroll);
+        let end = lines.len() - self.scroll;
+        let start = end.saturating_sub(height);
+        // keep the newest line at the bottom even if the log isn't full
+        let top = height - (end - start);
+        let mut view = frame.view(rect);
+        for (row, (line, fg)) in lines[start..end].iter().enumerate() {
+            view.print(Point2 { x: 0, y: top + row }, line, *fg, theme.bg);
+        }
+    }
+
+    fn key_down(&mut self, key: KeyCode, _modifiers: Modifiers) -> Response {
+        let page = self.page.max(1);
+        let scroll = match key {
+            KeyCode::Up => self.scroll.saturating_add(1),
+            KeyCode::Down => self.scroll.saturating_sub(1),
+            KeyCode::PageUp => self.scroll.saturating_add(page),
+            KeyCode::PageDown => self.scroll.saturating_sub(page),
+            KeyCode::Home => self.max_scroll,
+            KeyCode::End => 0,
+            _ => return Response::Ignored,
+        };
+        // the wrapped height is only known when drawing, so this uses the last one
+        self.scroll = scroll.min(self.max_scroll);
+        Response::Handled
+    }
+
+    fn focusable(&self) -> bool {
+        false
+    }
+}
+
+/// A single line of editable text. It scrolls sideways to keep the cursor in view.
+///
+/// Typed text is inserted at the cursor, the arrow keys, home and end move it, backspace and
+/// delete remove text, and return submits it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextInput {
+    text: String,
+    /// The cursor position, in chars.
+    cursor: usize,
+    /// The first char shown.
+    scroll: usize,
+    /// The most chars that can be typed.
+    pub max_len: Option<usize>,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replace the text, moving the cursor to the end.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.chars().count();
+    }
+
+    /// The byte index of the char at `index`.
+    fn byte_index(&self, index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(index)
+            .map(|(i, _)| i)
+            .unwrap_or_else(|| self.text.len())
+    }
+}
+
+impl Widget for TextInput {
+    fn draw(&mut self, frame: &mut Frame, rect: Rect, theme: &Theme, focused: bool) {
+        if rect.is_empty() {
+            return;
+        }
+        let width = rect.width();
+        let chars: Vec<char> = self.text.chars().collect();
+        let span_width =
+            |chars: &[char]| -> usize { chars.iter().map(|&ch| char_width(ch).unwrap_or(0)).sum() };
+        // leave room for the cursor after the last char
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        }
+        while self.scroll < self.cursor && span_width(&chars[self.scroll..self.cursor]) >= width {
+            self.scroll += 1;
+        }
+
+        let row = Rect::from_size(
+            rect.top_left,
+            Point2 {
+                x: width,
+                y: rect.height().min(1),
+            },
+        );
+        let mut view = frame.view(row);
+        view.fill(Char {
+            ch: ' ',
+            fg: theme.fg,
+            bg: theme.track,
+        });
+        let visible: String = chars[self.scroll..].iter().collect();
+        view.print(Point2 { x: 0, y: 0 }, &visible, theme.fg, theme.track);
+        if focused {
+            let x = span_width(&chars[self.scroll..self.cursor]);
+            let cell = &mut view[(x, 0)];
+            cell.fg = theme.focus_fg;
+            cell.bg = theme.focus_bg;
+        }
+    }
+
+    fn key_down(&mut self, key: KeyCode, _modifiers: Modifiers) -> Response {
+        let len = self.text.chars().count();
+        match key {
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(len),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = len,
+            KeyCode::Back if self.cursor > 0 => {
+                self.cursor -= 1;
+                let i = self.byte_index(self.cursor);
+                self.text.remove(i);
+            }
+            KeyCode::Delete if self.cursor < len => {
+                let i = self.byte_index(self.cursor);
+                self.text.remove(i);
+            }
+            KeyCode::Back | KeyCode::Delete => (),
+            KeyCode::Return | KeyCode::NumpadEnter => return Response::Activated,
+            _ => return Response::Ignored,
+        }
+        Response::Handled
+    }
+
+    fn text_input(&mut self, ch: char) -> Response {
+        let full = matches!(self.max_len, Some(max) if self.text.chars().count() >= max);
+        if !full {
+            let i = self.byte_index(self.cursor);
+            self.text.insert(i, ch);
+            self.cursor += 1;
+        }
+        Response::Handled
+    }
+}
+
+/// A horizontal bar showing how far something has got, with an optional label over it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProgressBar {
+    /// How full the bar is, from 0 to 1.
+    pub value: f32,
+    pub label: Option<String>,
+}
+
+/// Blocks filling the left of a cell, in eighths.
+const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+impl ProgressBar {
+    pub fn new(value: f32) -> Self {
+        ProgressBar { value, label: None }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+impl Widget for ProgressBar {
+    fn draw(&mut self, frame: &mut Frame, rect: Rect, theme: &Theme, _focused: bool) {
+        let width = rect.width();
+        let eighths = (self.value.clamp(0.0, 1.0) * (width * 8) as f32).round() as usize;
+        let mut view = frame.view(Rect::from_size(
+            rect.top_left,
+            Point2 {
+                x: width,
+                y: rect.height().min(1),
+            },
+        ));
+        for x in 0..width {
+            let filled = eighths.saturating_sub(x * 8).min(8);
+            view[(x, 0)] = match filled {
+                8 => Char {
+                    ch: ' ',
+                    fg: theme.fg,
+                    bg: theme.accent,
+                },
+                _ => Char {
+                    ch: EIGHTHS[filled],
+                    fg: theme.accent,
+                    bg: theme.track,
+                },
+            };
+        }
+        if let Some(label) = &self.label {
+            // keep each cell's background so the bar shows through the label
+            let x = width.saturating_sub(text::width(label)) / 2;
+            let mut line = Frame::new(Point2 { x: width, y: 1 });
+            line.print(Point2 { x, y: 0 }, label, theme.fg, Color::TRANSPARENT);
+            for x in 0..width {
+                let ch = line[(x, 0)];
+                if ch.ch != ' ' {
+                    view[(x, 0)].ch = ch.ch;
+                    view[(x, 0)].fg = ch.fg;
+                }
+            }
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        false
+    }
+}
+
+/// A modal box with a title, a message and a row of buttons, e.g. a yes/no prompt.
+///
+/// Left, right and Tab move between the buttons, return or space picks one (see `chosen`), and
+/// escape cancels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dialog {
+    pub title: String,
+    pub message: String,
+    pub buttons: Vec<String>,
+    focused: usize,
+}
+
+impl Dialog {
+    pub fn new(title: impl Into<String>, message: impl Into<String>, buttons: &[&str]) -> Self {
+        Dialog {
+            title: title.into(),
+            message: message.into(),
+            buttons: buttons.iter().map(|b| b.to_string()).collect(),
+            focused: 0,
+        }
+    }
+
+    /// The index of the focused button, which is the one picked when the dialog is activated.
+    pub fn chosen(&self) -> usize {
+        self.focused
+    }
+
+    /// The rectangle the dialog should be drawn in to be centered in `area`, sized to fit its
+    /// contents.
+    pub fn rect_in(&self, area: Rect) -> Rect {
+        let buttons: usize = self.buttons.iter().map(|b| button_width(b) + 1).sum();
+        let content = text::width(&self.message)
+            .max(buttons)
+            .max(text::width(&self.title) + 2);
+        // leave room for the border and a space either side
+        let inner = content.min(area.width() * 2 / 3).max(10);
+        let lines = text::wrap(&self.message, inner).len();
+        let size = Point2 {
+            x: (inner + 4).min(area.width()),
+            y: (lines + 4).min(area.height()),
+        };
+        Rect::from_size(
+            Point2 {
+                x: area.top_left.x + (area.width() - size.x) / 2,
+                y: area.top_left.y + (area.height() - size.y) / 2,
+            },
+            size,
+        )
+    }
+
+    /// Draw the dialog in the middle of the frame.
+    pub fn draw_centered(&mut self, frame: &mut Frame, theme: &Theme) {
+        let rect = self.rect_in(frame.rect());
+        self.draw(frame, rect, theme, true);
+    }
+}
+
+impl Widget for Dialog {
+    fn draw(&mut self, frame: &mut Frame, rect: Rect, theme: &Theme, focused: bool) {
+        Border::new(theme.border)
+            .fg(theme.accent)
+            .bg(theme.panel)
+            .fill(theme.panel)
+            .title(format!(" {} ", self.title), Align::Center)
+            .draw(frame, rect);
+        let inner = rect.deflate(1);
+        let (message, buttons) = inner.split_horizontally(inner.height().saturating_sub(1));
+        frame.print_wrapped(message, &self.message, Align::Center, theme.fg, theme.panel);
+
+        let total: usize = self.buttons.iter().map(|b| button_width(b)).sum::<usize>()
+            + self.buttons.len().saturating_sub(1);
+        let mut x = buttons.top_left.x + buttons.width().saturating_sub(total) / 2;
+        for (i, label) in self.buttons.iter().enumerate() {
+            let width = button_width(label);
+            let button = Rect::from_size(
+                Point2 {
+                    x,
+                    y: buttons.top_left.y,
+                },
+                Point2 { x: width, y: 1 },
+            )
+            .intersection(buttons);
+            let is_focused = focused && i == self.focused;
+            let (fg, bg) = if is_focused {
+                (theme.focus_fg, theme.focus_bg)
+            } else {
+                (theme.fg, theme.panel)
+            };
+            frame.print_wrapped(button, &format!("[ {} ]", label), Align::Left, fg, bg);
+            x += width + 1;
+        }
+    }
+
+    fn key_down(&mut self, key: KeyCode, modifiers: Modifiers) -> Response {
+        let len = self.buttons.len().max(1);
+        match key {
+            KeyCode::Left => self.focused = self.focused.saturating_sub(1),
+            KeyCode::Right => self.focused = (self.focused + 1).min(len - 1),
+            KeyCode::Tab if modifiers.shift => self.focused = (self.focused + len - 1) % len,
+            KeyCode::Tab => self.focused = (self.focused + 1) % len,
+            KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Space => return Response::Activated,
+            KeyCode::Escape => return Response::Cancelled,
+            _ => return Response::Ignored,
+        }
+        Response::Handled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::screen;
+
+    fn press(widget: &mut dyn Widget, key: KeyCode) -> Response {
+        widget.key_down(key, Modifiers::default())
+    }
+
+    fn type_text(widget: &mut dyn Widget, text: &str) {
+        for ch in text.chars() {
+            widget.text_input(ch);
+        }
+    }
+
+    /// Draw `widget` filling a new frame of the given size.
+    fn draw(widget: &mut dyn Widget, width: usize, height: usize, focused: bool) -> Frame {
+        let mut frame = Frame::new(Point2 {
+            x: width,
+            y: height,
+        });
+        let rect = frame.rect();
+        widget.draw(&mut frame, rect, &Theme::default(), focused);
+        frame
+    }
+
+    #[test]
+    fn focus_skips_widgets_that_cant_have_focus() {
+        let (label, button, bar, list, log) = (
+            Label::new("Name"),
+            Button::new("OK"),
+            ProgressBar::new(0.5),
+            List::default(),
+            MessageLog::new(),
+        );
+        let mut focus = Focus::new(&[&label, &button, &bar, &list, &log]);
+        assert_eq!(focus.current(), 1);
+        focus.next();
+        assert_eq!(focus.current(), 3);
+        focus.next();
+        assert_eq!(focus.current(), 1);
+        focus.prev();
+        assert!(focus.is(3));
+        focus.set(2);
+        assert_eq!(focus.current(), 3);
+        focus.set_focusable(3, false);
+        assert_eq!(focus.current(), 1);
+
+        let shift = Modifiers {
+            shift: true,
+            ..Modifiers::default()
+        };
+        focus.set_focusable(4, true);
+        assert_eq!(focus.key_down(KeyCode::Tab, shift), Response::Handled);
+        assert_eq!(focus.current(), 4);
+        assert_eq!(
+            focus.key_down(KeyCode::Tab, Modifiers::default()),
+            Response::Handled
+        );
+        assert_eq!(focus.current(), 1);
+        assert_eq!(
+            focus.key_down(KeyCode::Up, Modifiers::default()),
+            Response::Ignored
+        );
+
+        // with nothing to focus, focus stays put
+        let mut focus = Focus::new(&[&label, &bar]);
+        focus.next();
+        focus.prev();
+        assert_eq!(focus.current(), 0);
+        Focus::new(&[]).next();
+    }
+
+    #[test]
+    fn list_scrolls_to_the_selection() {
+        let mut list = List::new("abcde".chars().map(String::from).collect());
+        let frame = draw(&mut list, 3, 3, true);
+        assert_eq!(screen(&frame), "a █\nb │\nc │");
+        assert_eq!(frame[(1, 0)].bg, Theme::default().focus_bg);
+        assert_eq!(frame[(1, 1)].bg, Theme::default().bg);
+
+        assert_eq!(press(&mut list, KeyCode::End), Response::Handled);
+        assert_eq!(list.selected_item(), Some("e"));
+        assert_eq!(screen(&draw(&mut list, 3, 3, true)), "c │\nd │\ne █");
+        press(&mut list, KeyCode::Up);
+        press(&mut list, KeyCode::Up);
+        assert_eq!(screen(&draw(&mut list, 3, 3, true)), "c │\nd │\ne █");
+        press(&mut list, KeyCode::Up);
+        let frame = draw(&mut list, 3, 3, false);
+        assert_eq!(screen(&frame), "b │\nc █\nd │");
+        assert_eq!(frame[(0, 0)].bg, Theme::default().selected_bg);
+
+        // paging goes by the height last drawn
+        press(&mut list, KeyCode::PageDown);
+        assert_eq!(list.selected(), 4);
+        press(&mut list, KeyCode::PageUp);
+        assert_eq!(list.selected(), 1);
+        press(&mut list, KeyCode::Home);
+        assert_eq!(list.selected(), 0);
+        assert_eq!(press(&mut list, KeyCode::Return), Response::Activated);
+        assert_eq!(press(&mut list, KeyCode::A), Response::Ignored);
+    }
+
+    #[test]
+    fn short_lists_have_no_scrollbar() {
+        let mut list = List::new(vec!["one".into(), "two".into()]);
+        list.select(5);
+        assert_eq!(list.selected(), 1);
+        assert_eq!(screen(&draw(&mut list, 4, 3, true)), "one \ntwo \n    ");
+
+        let mut empty = List::default();
+        assert_eq!(empty.selected_item(), None);
+        assert_eq!(press(&mut empty, KeyCode::Down), Response::Ignored);
+    }
+
+    #[test]
+    fn empty_rects_draw_nothing() {
+        let mut list = List::new("abcde".chars().map(String::from).collect());
+        let mut input = TextInput::new();
+        input.set_text("hi");
+        let mut widgets: [&mut dyn Widget; 2] = [&mut list, &mut input];
+        for widget in &mut widgets {
+            for &(width, height) in &[(0, 3), (3, 0), (0, 0)] {
+                let frame = draw(&mut **widget, width, height, true);
+                assert_eq!(
+                    frame.size(),
+                    Point2 {
+                        x: width,
+                        y: height
+                    }
+                );
+            }
+            let mut frame = Frame::new(Point2 { x: 3, y: 3 });
+            widget.draw(
+                &mut frame,
+                Rect::from_parts(1, 0, 1, 3),
+                &Theme::default(),
+                true,
+            );
+            assert_eq!(screen(&frame), "   \n   \n   ");
+        }
+    }
+
+    #[test]
+    fn text_input_edits_at_the_cursor() {
+        let mut input = TextInput::new();
+        type_text(&mut input, "hllo");
+        for _ in 0..3 {
+            assert_eq!(press(&mut input, KeyCode::Left), Response::Handled);
+        }
+        type_text(&mut input, "e");
+        assert_eq!(input.text(), "hello");
+        press(&mut input, KeyCode::Back);
+        assert_eq!(input.text(), "hllo");
+        press(&mut input, KeyCode::Delete);
+        assert_eq!(input.text(), "hlo");
+        press(&mut input, KeyCode::End);
+        press(&mut input, KeyCode::Back);
+        assert_eq!(input.text(), "hl");
+        press(&mut input, KeyCode::Delete);
+        press(&mut input, KeyCode::Right);
+        assert_eq!(input.text(), "hl");
+        press(&mut input, KeyCode::Home);
+        press(&mut input, KeyCode::Back);
+        press(&mut input, KeyCode::Delete);
+        assert_eq!(input.text(), "l");
+        assert_eq!(press(&mut input, KeyCode::Return), Response::Activated);
+        assert_eq!(press(&mut input, KeyCode::Up), Response::Ignored);
+
+        // the cursor counts chars, not bytes
+        input.set_text("héllo");
+        for _ in 0..3 {
+            press(&mut input, KeyCode::Left);
+        }
+        press(&mut input, KeyCode::Back);
+        type_text(&mut input, "ö");
+        assert_eq!(input.text(), "höllo");
+    }
+
+    #[test]
+    fn text_input_max_len() {
+        let mut input = TextInput {
+            max_len: Some(3),
+            ..TextInput::new()
+        };
+        type_text(&mut input, "abcd");
+        assert_eq!(input.text(), "abc");
+        press(&mut input, KeyCode::Home);
+        assert_eq!(input.text_input('z'), Response::Handled);
+        assert_eq!(input.text(), "abc");
+    }
+
+    #[test]
+    fn text_input_scrolls_to_the_cursor() {
+        let theme = Theme::default();
+        let mut input = TextInput::new();
+        input.set_text("abcdef");
+        let frame = draw(&mut input, 4, 1, true);
+        assert_eq!(screen(&frame), "def ");
+        assert_eq!(frame[(3, 0)].bg, theme.focus_bg);
+        assert_eq!(frame[(2, 0)].bg, theme.track);
+
+        press(&mut input, KeyCode::Home);
+        let frame = draw(&mut input, 4, 1, true);
+        assert_eq!(screen(&frame), "abcd");
+        assert_eq!(frame[(0, 0)].bg, theme.focus_bg);
+        // moving back to the end only scrolls as far as it needs to
+        for _ in 0..4 {
+            press(&mut input, KeyCode::Right);
+        }
+        assert_eq!(screen(&draw(&mut input, 4, 1, true)), "bcde");
+        let frame = draw(&mut input, 4, 1, false);
+        assert!((0..4).all(|x| frame[(x, 0)].bg == theme.track));
+    }
+
+    #[test]
+    fn dialog_layout() {
+        let dialog = Dialog::new("Quit", "Really quit?", &["Yes", "No"]);
+        // sized to fit the buttons, plus the border and a space either side
+        assert_eq!(
+            dialog.rect_in(Rect::from_parts(0, 0, 40, 20)),
+            Rect::from_size(Point2 { x: 10, y: 7 }, Point2 { x: 19, y: 5 })
+        );
+        assert_eq!(
+            dialog.rect_in(Rect::from_parts(5, 5, 45, 25)),
+            Rect::from_size(Point2 { x: 15, y: 12 }, Point2 { x: 19, y: 5 })
+        );
+        // shrunk to fit small areas, wrapping the message
+        assert_eq!(
+            dialog.rect_in(Rect::from_parts(0, 0, 12, 4)),
+            Rect::from_size(Point2 { x: 0, y: 0 }, Point2 { x: 12, y: 4 })
+        );
+        let long = Dialog::new("", "word ".repeat(20), &["OK"]);
+        assert_eq!(
+            long.rect_in(Rect::from_parts(0, 0, 30, 20)),
+            Rect::from_size(Point2 { x: 3, y: 5 }, Point2 { x: 24, y: 9 })
+        );
+    }
+
+    #[test]
+    fn dialog_draws_message_and_buttons() {
+        let mut dialog = Dialog::new("Quit", "Really quit?", &["Yes", "No"]);
+        let mut frame = Frame::new(Point2 { x: 23, y: 7 });
+        dialog.draw_centered(&mut frame, &Theme::default());
+        assert_eq!(
+            screen(&frame),
+            [
+                "                       ",
+                "  ┌───── Quit ──────┐  ",
+                "  │  Really quit?   │  ",
+                "  │                 │  ",
+                "  │ [ Yes ] [ No ]  │  ",
+                "  └─────────────────┘  ",
+                "                       ",
+            ]
+            .join("\n")
+        );
+        assert_eq!(frame[(4, 4)].bg, Theme::default().focus_bg);
+        assert_eq!(frame[(12, 4)].bg, Theme::default().panel);
+    }
+
+    #[test]
+    fn dialog_keys_move_between_buttons() {
+        let mut dialog = Dialog::new("Quit", "Really quit?", &["Yes", "No", "Maybe"]);
+        let shift = Modifiers {
+            shift: true,
+            ..Modifiers::default()
+        };
+        let mut chosen = |key, modifiers| {
+            assert_eq!(dialog.key_down(key, modifiers), Response::Handled);
+            dialog.chosen()
+        };
+        assert_eq!(chosen(KeyCode::Right, Modifiers::default()), 1);
+        assert_eq!(chosen(KeyCode::Right, Modifiers::default()), 2);
+        assert_eq!(chosen(KeyCode::Right, Modifiers::default()), 2);
+        assert_eq!(chosen(KeyCode::Tab, Modifiers::default()), 0);
+        assert_eq!(chosen(KeyCode::Tab, shift), 2);
+        assert_eq!(chosen(KeyCode::Left, Modifiers::default()), 1);
+        assert_eq!(press(&mut dialog, KeyCode::Space), Response::Activated);
+        assert_eq!(press(&mut dialog, KeyCode::Escape), Response::Cancelled);
+        assert_eq!(press(&mut dialog, KeyCode::A), Response::Ignored);
+        assert_eq!(dialog.chosen(), 1);
+    }
+
+    #[test]
+    fn progress_bars_fill_in_eighths() {
+        let theme = Theme::default();
+        let mut bar = ProgressBar::new(0.3);
+        let frame = draw(&mut bar, 4, 1, false);
+        assert_eq!(screen(&frame), " ▎  ");
+        assert_eq!(frame[(0, 0)].bg, theme.accent);
+        assert_eq!(frame[(1, 0)].bg, theme.track);
+
+        bar.value = 2.0;
+        let frame = draw(&mut bar, 4, 1, false);
+        assert!((0..4).all(|x| frame[(x, 0)].bg == theme.accent));
+        bar.value = -1.0;
+        let frame = draw(&mut bar, 4, 1, false);
+        assert!((0..4).all(|x| frame[(x, 0)].bg == theme.track));
+
+        // labels keep the bar's background
+        let mut bar = ProgressBar::new(0.5).label("ab");
+        let frame = draw(&mut bar, 4, 1, false);
+        assert_eq!(screen(&frame), " ab ");
+        assert_eq!(frame[(1, 0)].bg, theme.accent);
+        assert_eq!(frame[(2, 0)].bg, theme.track);
+    }
+
+    #[test]
+    fn message_logs_keep_the_newest_at_the_bottom() {
+        let mut log = MessageLog::new();
+        log.push("hello", Color::RED);
+        let frame = draw(&mut log, 5, 3, false);
+        assert_eq!(screen(&frame), "     \n     \nhello");
+        assert_eq!(frame[(0, 2)].fg, Color::RED);
+
+        log.push("a long one", Color::WHITE);
+        assert_eq!(screen(&draw(&mut log, 5, 3, false)), "a    \nlong \none  ");
+
+        log.max_messages = 2;
+        log.push("x", Color::WHITE);
+        log.push("y", Color::WHITE);
+        assert_eq!(screen(&draw(&mut log, 5, 3, false)), "     \nx    \ny    ");
+    }
+
+    #[test]
+    fn message_logs_scroll_back() {
+        let mut log = MessageLog::new();
+        for message in &["1", "2", "3", "4", "5"] {
+            log.push(*message, Color::WHITE);
+        }
+        assert_eq!(screen(&draw(&mut log, 1, 2, false)), "4\n5");
+        assert_eq!(press(&mut log, KeyCode::Up), Response::Handled);
+        assert_eq!(screen(&draw(&mut log, 1, 2, false)), "3\n4");
+        press(&mut log, KeyCode::Home);
+        assert_eq!(screen(&draw(&mut log, 1, 2, false)), "1\n2");
+        press(&mut log, KeyCode::PageDown);
+        assert_eq!(screen(&draw(&mut log, 1, 2, false)), "3\n4");
+        press(&mut log, KeyCode::End);
+        assert_eq!(screen(&draw(&mut log, 1, 2, false)), "4\n5");
+        assert_eq!(press(&mut log, KeyCode::A), Response::Ignored);
+    }
+
+    #[test]
+    fn message_logs_stop_scrolling_at_the_top() {
+        let mut log = MessageLog::new();
+        for message in &["1", "2", "3", "4", "5"] {
+            log.push(*message, Color::WHITE);
+        }
+        // nothing to scroll until the height is known
+        press(&mut log, KeyCode::Home);
+        assert_eq!(screen(&draw(&mut log, 1, 2, false)), "4\n5");
+
+        press(&mut log, KeyCode::Home);
+        assert_eq!(press(&mut log, KeyCode::Up), Response::Handled);
+        assert_eq!(screen(&draw(&mut log, 1, 2, false)), "1\n2");
+        press(&mut log, KeyCode::Home);
+        press(&mut log, KeyCode::PageUp);
+        press(&mut log, KeyCode::PageUp);
+        // one step down from the top, not from somewhere past it
+        press(&mut log, KeyCode::Down);
+        assert_eq!(screen(&draw(&mut log, 1, 2, false)), "2\n3");
+    }
+}
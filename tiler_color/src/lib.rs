@@ -0,0 +1,1302 @@
+//! Colors, and parsing them from the syntax used in CSS: names, hex (`#rgb`, `#rgba`, `#rrggbb`
+//! and `#rrggbbaa`) and the `rgb`, `rgba`, `hsl` and `hsla` functions.
+//!
+//! This is shared between `tiler` and `tiler_derive`, so colors are parsed the same way at compile
+//! time and at runtime.
+use nom::IResult;
+use std::{error::Error, fmt, ops::Range, str::FromStr};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    /// Fully transparent, so nothing is drawn.
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0);
+
+    // The CSS named colors.
+    pub const INDIAN_RED: Color = Color::rgb(205, 92, 92);
+    pub const LIGHT_CORAL: Color = Color::rgb(240, 128, 128);
+    pub const SALMON: Color = Color::rgb(250, 128, 114);
+    pub const DARK_SALMON: Color = Color::rgb(233, 150, 122);
+    pub const LIGHT_SALMON: Color = Color::rgb(255, 160, 122);
+    pub const CRIMSON: Color = Color::rgb(220, 20, 60);
+    pub const RED: Color = Color::rgb(255, 0, 0);
+    pub const FIRE_BRICK: Color = Color::rgb(178, 34, 34);
+    pub const DARK_RED: Color = Color::rgb(139, 0, 0);
+    pub const PINK: Color = Color::rgb(255, 192, 203);
+    pub const LIGHT_PINK: Color = Color::rgb(255, 182, 193);
+    pub const HOT_PINK: Color = Color::rgb(255, 105, 180);
+    pub const DEEP_PINK: Color = Color::rgb(255, 20, 147);
+    pub const MEDIUM_VIOLET_RED: Color = Color::rgb(199, 21, 133);
+    pub const PALE_VIOLET_RED: Color = Color::rgb(219, 112, 147);
+    pub const CORAL: Color = Color::rgb(255, 127, 80);
+    pub const TOMATO: Color = Color::rgb(255, 99, 71);
+    pub const ORANGE_RED: Color = Color::rgb(255, 69, 0);
+    pub const DARK_ORANGE: Color = Color::rgb(255, 140, 0);
+    pub const ORANGE: Color = Color::rgb(255, 165, 0);
+    pub const GOLD: Color = Color::rgb(255, 215, 0);
+    pub const YELLOW: Color = Color::rgb(255, 255, 0);
+    pub const LIGHT_YELLOW: Color = Color::rgb(255, 255, 224);
+    pub const LEMON_CHIFFON: Color = Color::rgb(255, 250, 205);
+    pub const LIGHT_GOLDENROD_YELLOW: Color = Color::rgb(250, 250, 210);
+    pub const PAPAYA_WHIP: Color = Color::rgb(255, 239, 213);
+    pub const MOCCASIN: Color = Color::rgb(255, 228, 181);
+    pub const PEACH_PUFF: Color = Color::rgb(255, 218, 185);
+    pub const PALE_GOLDENROD: Color = Color::rgb(238, 232, 170);
+    pub const KHAKI: Color = Color::rgb(240, 230, 140);
+    pub const DARK_KHAKI: Color = Color::rgb(189, 183, 107);
+    pub const LAVENDER: Color = Color::rgb(230, 230, 250);
+    pub const THISTLE: Color = Color::rgb(216, 191, 216);
+    pub const PLUM: Color = Color::rgb(221, 160, 221);
+    pub const VIOLET: Color = Color::rgb(238, 130, 238);
+    pub const ORCHID: Color = Color::rgb(218, 112, 214);
+    pub const FUCHSIA: Color = Color::rgb(255, 0, 255);
+    pub const MAGENTA: Color = Color::rgb(255, 0, 255);
+    pub const MEDIUM_ORCHID: Color = Color::rgb(186, 85, 211);
+    pub const MEDIUM_PURPLE: Color = Color::rgb(147, 112, 219);
+    pub const REBECCA_PURPLE: Color = Color::rgb(102, 51, 153);
+    pub const BLUE_VIOLET: Color = Color::rgb(138, 43, 226);
+    pub const DARK_VIOLET: Color = Color::rgb(148, 0, 211);
+    pub const DARK_ORCHID: Color = Color::rgb(153, 50, 204);
+    pub const DARK_MAGENTA: Color = Color::rgb(139, 0, 139);
+    pub const PURPLE: Color = Color::rgb(128, 0, 128);
+    pub const INDIGO: Color = Color::rgb(75, 0, 130);
+    pub const SLATE_BLUE: Color = Color::rgb(106, 90, 205);
+    pub const DARK_SLATE_BLUE: Color = Color::rgb(72, 61, 139);
+    pub const MEDIUM_SLATE_BLUE: Color = Color::rgb(123, 104, 238);
+    pub const GREEN_YELLOW: Color = Color::rgb(173, 255, 47);
+    pub const CHARTREUSE: Color = Color::rgb(127, 255, 0);
+    pub const LAWN_GREEN: Color = Color::rgb(124, 252, 0);
+    pub const LIME: Color = Color::rgb(0, 255, 0);
+    pub const LIME_GREEN: Color = Color::rgb(50, 205, 50);
+    pub const PALE_GREEN: Color = Color::rgb(152, 251, 152);
+    pub const LIGHT_GREEN: Color = Color::rgb(144, 238, 144);
+    pub const MEDIUM_SPRING_GREEN: Color = Color::rgb(0, 250, 154);
+    pub const SPRING_GREEN: Color = Color::rgb(0, 255, 127);
+    pub const MEDIUM_SEA_GREEN: Color = Color::rgb(60, 179, 113);
+    pub const SEA_GREEN: Color = Color::rgb(46, 139, 87);
+    pub const FOREST_GREEN: Color = Color::rgb(34, 139, 34);
+    pub const GREEN: Color = Color::rgb(0, 128, 0);
+    pub const DARK_GREEN: Color = Color::rgb(0, 100, 0);
+    pub const YELLOW_GREEN: Color = Color::rgb(154, 205, 50);
+    pub const OLIVE_DRAB: Color = Color::rgb(107, 142, 35);
+    pub const OLIVE: Color = Color::rgb(128, 128, 0);
+    pub const DARK_OLIVE_GREEN: Color = Color::rgb(85, 107, 47);
+    pub const MEDIUM_AQUAMARINE: Color = Color::rgb(102, 205, 170);
+    pub const DARK_SEA_GREEN: Color = Color::rgb(143, 188, 139);
+    pub const LIGHT_SEA_GREEN: Color = Color::rgb(32, 178, 170);
+    pub const DARK_CYAN: Color = Color::rgb(0, 139, 139);
+    pub const TEAL: Color = Color::rgb(0, 128, 128);
+    pub const AQUA: Color = Color::rgb(0, 255, 255);
+    pub const CYAN: Color = Color::rgb(0, 255, 255);
+    pub const LIGHT_CYAN: Color = Color::rgb(224, 255, 255);
+    pub const PALE_TURQUOISE: Color = Color::rgb(175, 238, 238);
+    pub const AQUAMARINE: Color = Color::rgb(127, 255, 212);
+    pub const TURQUOISE: Color = Color::rgb(64, 224, 208);
+    pub const MEDIUM_TURQUOISE: Color = Color::rgb(72, 209, 204);
+    pub const DARK_TURQUOISE: Color = Color::rgb(0, 206, 209);
+    pub const CADET_BLUE: Color = Color::rgb(95, 158, 160);
+    pub const STEEL_BLUE: Color = Color::rgb(70, 130, 180);
+    pub const LIGHT_STEEL_BLUE: Color = Color::rgb(176, 196, 222);
+    pub const POWDER_BLUE: Color = Color::rgb(176, 224, 230);
+    pub const LIGHT_BLUE: Color = Color::rgb(173, 216, 230);
+    pub const SKY_BLUE: Color = Color::rgb(135, 206, 235);
+    pub const LIGHT_SKY_BLUE: Color = Color::rgb(135, 206, 250);
+    pub const DEEP_SKY_BLUE: Color = Color::rgb(0, 191, 255);
+    pub const DODGER_BLUE: Color = Color::rgb(30, 144, 255);
+    pub const CORNFLOWER_BLUE: Color = Color::rgb(100, 149, 237);
+    pub const ROYAL_BLUE: Color = Color::rgb(65, 105, 225);
+    pub const BLUE: Color = Color::rgb(0, 0, 255);
+    pub const MEDIUM_BLUE: Color = Color::rgb(0, 0, 205);
+    pub const DARK_BLUE: Color = Color::rgb(0, 0, 139);
+    pub const NAVY: Color = Color::rgb(0, 0, 128);
+    pub const MIDNIGHT_BLUE: Color = Color::rgb(25, 25, 112);
+    pub const CORNSILK: Color = Color::rgb(255, 248, 220);
+    pub const BLANCHED_ALMOND: Color = Color::rgb(255, 235, 205);
+    pub const BISQUE: Color = Color::rgb(255, 228, 196);
+    pub const NAVAJO_WHITE: Color = Color::rgb(255, 222, 173);
+    pub const WHEAT: Color = Color::rgb(245, 222, 179);
+    pub const BURLY_WOOD: Color = Color::rgb(222, 184, 135);
+    pub const TAN: Color = Color::rgb(210, 180, 140);
+    pub const ROSY_BROWN: Color = Color::rgb(188, 143, 143);
+    pub const SANDY_BROWN: Color = Color::rgb(244, 164, 96);
+    pub const GOLDENROD: Color = Color::rgb(218, 165, 32);
+    pub const DARK_GOLDENROD: Color = Color::rgb(184, 134, 11);
+    pub const PERU: Color = Color::rgb(205, 133, 63);
+    pub const CHOCOLATE: Color = Color::rgb(210, 105, 30);
+    pub const SADDLE_BROWN: Color = Color::rgb(139, 69, 19);
+    pub const SIENNA: Color = Color::rgb(160, 82, 45);
+    pub const BROWN: Color = Color::rgb(165, 42, 42);
+    pub const MAROON: Color = Color::rgb(128, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const SNOW: Color = Color::rgb(255, 250, 250);
+    pub const HONEY_DEW: Color = Color::rgb(240, 255, 240);
+    pub const MINT_CREAM: Color = Color::rgb(245, 255, 250);
+    pub const AZURE: Color = Color::rgb(240, 255, 255);
+    pub const ALICE_BLUE: Color = Color::rgb(240, 248, 255);
+    pub const GHOST_WHITE: Color = Color::rgb(248, 248, 255);
+    pub const WHITE_SMOKE: Color = Color::rgb(245, 245, 245);
+    pub const SEA_SHELL: Color = Color::rgb(255, 245, 238);
+    pub const BEIGE: Color = Color::rgb(245, 245, 220);
+    pub const OLD_LACE: Color = Color::rgb(253, 245, 230);
+    pub const FLORAL_WHITE: Color = Color::rgb(255, 250, 240);
+    pub const IVORY: Color = Color::rgb(255, 255, 240);
+    pub const ANTIQUE_WHITE: Color = Color::rgb(250, 235, 215);
+    pub const LINEN: Color = Color::rgb(250, 240, 230);
+    pub const LAVENDER_BLUSH: Color = Color::rgb(255, 240, 245);
+    pub const MISTY_ROSE: Color = Color::rgb(255, 228, 225);
+    pub const GAINSBORO: Color = Color::rgb(220, 220, 220);
+    pub const LIGHT_GRAY: Color = Color::rgb(211, 211, 211);
+    pub const SILVER: Color = Color::rgb(192, 192, 192);
+    pub const DARK_GRAY: Color = Color::rgb(169, 169, 169);
+    pub const GRAY: Color = Color::rgb(128, 128, 128);
+    pub const DIM_GRAY: Color = Color::rgb(105, 105, 105);
+    pub const LIGHT_SLATE_GRAY: Color = Color::rgb(119, 136, 153);
+    pub const SLATE_GRAY: Color = Color::rgb(112, 128, 144);
+    pub const DARK_SLATE_GRAY: Color = Color::rgb(47, 79, 79);
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+
+    #[inline]
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::rgba(r, g, b, u8::MAX)
+    }
+
+    #[inline]
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    #[inline]
+    pub const fn mono(level: u8) -> Self {
+        Self::rgb(level, level, level)
+    }
+
+    /// Parse a color like you would in CSS, e.g. `red`, `#f80`, `#ff880080`,
+    /// `rgba(255, 136, 0, 0.5)`, `rgb(100%, 50%, 0%)` or `hsl(30deg, 100%, 50%)`.
+    ///
+    /// Channels of `rgb` are numbers from 0 to 255 or percentages, hues are in degrees (with or
+    /// without `deg`), saturation and lightness are percentages (with or without `%`), and alpha
+    /// is a number from 0 to 1 or a percentage. Names ignore case, and accept both `gray` and
+    /// `grey`.
+    pub fn parse(input: &str) -> Result<Self, ParseColorError> {
+        Ok(RawColor::parse(input)?.into())
+    }
+
+    /// The same color with a different alpha.
+    #[inline]
+    pub const fn with_alpha(self, a: u8) -> Self {
+        Self::rgba(self.r, self.g, self.b, a)
+    }
+
+    /// Whether the color is completely transparent.
+    #[inline]
+    pub fn is_transparent(self) -> bool {
+        self.a == 0
+    }
+
+    /// The color with each channel between 0 and 1.
+    pub fn to_f32(self) -> [f32; 4] {
+        self.into()
+    }
+
+    /// Blend between two colors in (approximately) linear light, so the halfway point isn't too
+    /// dark. `t` is how far to go from `fg` (at 0) to `bg` (at 1).
+    pub fn interp(bg: Color, fg: Color, t: f32) -> Color {
+        let fgr = fg.r as f32 / 255.0;
+        let fgg = fg.g as f32 / 255.0;
+        let fgb = fg.b as f32 / 255.0;
+        let fga = fg.a as f32 / 255.0;
+        let bgr = bg.r as f32 / 255.0;
+        let bgg = bg.g as f32 / 255.0;
+        let bgb = bg.b as f32 / 255.0;
+        let bga = bg.a as f32 / 255.0;
+        let r = ((1.0 - t) * fgr * fgr + t * bgr * bgr).sqrt();
+        let g = ((1.0 - t) * fgg * fgg + t * bgg * bgg).sqrt();
+        let b = ((1.0 - t) * fgb * fgb + t * bgb * bgb).sqrt();
+        let a = (1.0 - t) * fga + t * bga;
+        Color {
+            r: (r * 255.0).round() as u8,
+            g: (g * 255.0).round() as u8,
+            b: (b * 255.0).round() as u8,
+            a: (a * 255.0).round() as u8,
+        }
+    }
+
+    /// The color with its alpha multiplied by `opacity`, which is clamped to between 0 and 1.
+    pub fn faded(self, opacity: f32) -> Color {
+        let opacity = opacity.clamp(0.0, 1.0);
+        self.with_alpha((self.a as f32 * opacity).round() as u8)
+    }
+
+    /// The color you get by drawing `top` over this one, using `top`'s alpha.
+    pub fn over(self, top: Color) -> Color {
+        let top_a = top.a as f32 / 255.0;
+        let a = top_a + self.a as f32 / 255.0 * (1.0 - top_a);
+        if a <= 0.0 {
+            return Color::TRANSPARENT;
+        }
+        // how much of the result comes from `top`
+        let t = top_a / a;
+        Color::interp(self, top, 1.0 - t).with_alpha((a * 255.0).round() as u8)
+    }
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Color::TRANSPARENT
+    }
+}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Color::parse(input)
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(color: Color) -> Self {
+        [
+            color.r as f32 / 255.0,
+            color.g as f32 / 255.0,
+            color.b as f32 / 255.0,
+            color.a as f32 / 255.0,
+        ]
+    }
+}
+
+impl From<[f32; 4]> for Color {
+    /// Channels are clamped to between 0 and 1.
+    fn from([r, g, b, a]: [f32; 4]) -> Self {
+        let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Color::rgba(to_u8(r), to_u8(g), to_u8(b), to_u8(a))
+    }
+}
+
+impl From<Color> for [u8; 4] {
+    fn from(color: Color) -> Self {
+        [color.r, color.g, color.b, color.a]
+    }
+}
+
+impl From<[u8; 4]> for Color {
+    fn from([r, g, b, a]: [u8; 4]) -> Self {
+        Color::rgba(r, g, b, a)
+    }
+}
+
+impl From<Color> for mint::Vector4<f32> {
+    fn from(color: Color) -> Self {
+        <[f32; 4]>::from(color).into()
+    }
+}
+
+impl From<mint::Vector4<f32>> for Color {
+    fn from(v: mint::Vector4<f32>) -> Self {
+        <[f32; 4]>::from(v).into()
+    }
+}
+
+impl From<Color> for mint::Vector3<f32> {
+    /// Drops the alpha channel.
+    fn from(color: Color) -> Self {
+        let [r, g, b, _] = <[f32; 4]>::from(color);
+        [r, g, b].into()
+    }
+}
+
+impl From<mint::Vector3<f32>> for Color {
+    /// The color is opaque.
+    fn from(v: mint::Vector3<f32>) -> Self {
+        [v.x, v.y, v.z, 1.0].into()
+    }
+}
+
+/// An error parsing a color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorError {
+    msg: String,
+    span: Range<usize>,
+}
+
+impl ParseColorError {
+    fn new(msg: impl Into<String>, span: Range<usize>) -> Self {
+        ParseColorError {
+            msg: msg.into(),
+            span,
+        }
+    }
+
+    /// The byte range of the input that the error is about.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+impl Error for ParseColorError {}
+
+impl From<RawColor> for Color {
+    fn from(raw_color: RawColor) -> Self {
+        use RawColor::*;
+        match raw_color {
+            Rgba(r, g, b, a) => Color::rgba(r, g, b, a),
+            Hsla(h, s, l, a) => hsl_to_rgb(h, s, l).with_alpha(a),
+            IndianRed => Color::INDIAN_RED,
+            LightCoral => Color::LIGHT_CORAL,
+            Salmon => Color::SALMON,
+            DarkSalmon => Color::DARK_SALMON,
+            LightSalmon => Color::LIGHT_SALMON,
+            Crimson => Color::CRIMSON,
+            Red => Color::RED,
+            FireBrick => Color::FIRE_BRICK,
+            DarkRed => Color::DARK_RED,
+            Pink => Color::PINK,
+            LightPink => Color::LIGHT_PINK,
+            HotPink => Color::HOT_PINK,
+            DeepPink => Color::DEEP_PINK,
+            MediumVioletRed => Color::MEDIUM_VIOLET_RED,
+            PaleVioletRed => Color::PALE_VIOLET_RED,
+            Coral => Color::CORAL,
+            Tomato => Color::TOMATO,
+            OrangeRed => Color::ORANGE_RED,
+            DarkOrange => Color::DARK_ORANGE,
+            Orange => Color::ORANGE,
+            Gold => Color::GOLD,
+            Yellow => Color::YELLOW,
+            LightYellow => Color::LIGHT_YELLOW,
+            LemonChiffon => Color::LEMON_CHIFFON,
+            LightGoldenrodYellow => Color::LIGHT_GOLDENROD_YELLOW,
+            PapayaWhip => Color::PAPAYA_WHIP,
+            Moccasin => Color::MOCCASIN,
+            PeachPuff => Color::PEACH_PUFF,
+            PaleGoldenrod => Color::PALE_GOLDENROD,
+            Khaki => Color::KHAKI,
+            DarkKhaki => Color::DARK_KHAKI,
+            Lavender => Color::LAVENDER,
+            Thistle => Color::THISTLE,
+            Plum => Color::PLUM,
+            Violet => Color::VIOLET,
+            Orchid => Color::ORCHID,
+            Fuchsia => Color::FUCHSIA,
+            Magenta => Color::MAGENTA,
+            MediumOrchid => Color::MEDIUM_ORCHID,
+            MediumPurple => Color::MEDIUM_PURPLE,
+            RebeccaPurple => Color::REBECCA_PURPLE,
+            BlueViolet => Color::BLUE_VIOLET,
+            DarkViolet => Color::DARK_VIOLET,
+            DarkOrchid => Color::DARK_ORCHID,
+            DarkMagenta => Color::DARK_MAGENTA,
+            Purple => Color::PURPLE,
+            Indigo => Color::INDIGO,
+            SlateBlue => Color::SLATE_BLUE,
+            DarkSlateBlue => Color::DARK_SLATE_BLUE,
+            MediumSlateBlue => Color::MEDIUM_SLATE_BLUE,
+            GreenYellow => Color::GREEN_YELLOW,
+            Chartreuse => Color::CHARTREUSE,
+            LawnGreen => Color::LAWN_GREEN,
+            Lime => Color::LIME,
+            LimeGreen => Color::LIME_GREEN,
+            PaleGreen => Color::PALE_GREEN,
+            LightGreen => Color::LIGHT_GREEN,
+            MediumSpringGreen => Color::MEDIUM_SPRING_GREEN,
+            SpringGreen => Color::SPRING_GREEN,
+            MediumSeaGreen => Color::MEDIUM_SEA_GREEN,
+            SeaGreen => Color::SEA_GREEN,
+            ForestGreen => Color::FOREST_GREEN,
+            Green => Color::GREEN,
+            DarkGreen => Color::DARK_GREEN,
+            YellowGreen => Color::YELLOW_GREEN,
+            OliveDrab => Color::OLIVE_DRAB,
+            Olive => Color::OLIVE,
+            DarkOliveGreen => Color::DARK_OLIVE_GREEN,
+            MediumAquamarine => Color::MEDIUM_AQUAMARINE,
+            DarkSeaGreen => Color::DARK_SEA_GREEN,
+            LightSeaGreen => Color::LIGHT_SEA_GREEN,
+            DarkCyan => Color::DARK_CYAN,
+            Teal => Color::TEAL,
+            Aqua => Color::AQUA,
+            Cyan => Color::CYAN,
+            LightCyan => Color::LIGHT_CYAN,
+            PaleTurquoise => Color::PALE_TURQUOISE,
+            Aquamarine => Color::AQUAMARINE,
+            Turquoise => Color::TURQUOISE,
+            MediumTurquoise => Color::MEDIUM_TURQUOISE,
+            DarkTurquoise => Color::DARK_TURQUOISE,
+            CadetBlue => Color::CADET_BLUE,
+            SteelBlue => Color::STEEL_BLUE,
+            LightSteelBlue => Color::LIGHT_STEEL_BLUE,
+            PowderBlue => Color::POWDER_BLUE,
+            LightBlue => Color::LIGHT_BLUE,
+            SkyBlue => Color::SKY_BLUE,
+            LightSkyBlue => Color::LIGHT_SKY_BLUE,
+            DeepSkyBlue => Color::DEEP_SKY_BLUE,
+            DodgerBlue => Color::DODGER_BLUE,
+            CornflowerBlue => Color::CORNFLOWER_BLUE,
+            RoyalBlue => Color::ROYAL_BLUE,
+            Blue => Color::BLUE,
+            MediumBlue => Color::MEDIUM_BLUE,
+            DarkBlue => Color::DARK_BLUE,
+            Navy => Color::NAVY,
+            MidnightBlue => Color::MIDNIGHT_BLUE,
+            Cornsilk => Color::CORNSILK,
+            BlanchedAlmond => Color::BLANCHED_ALMOND,
+            Bisque => Color::BISQUE,
+            NavajoWhite => Color::NAVAJO_WHITE,
+            Wheat => Color::WHEAT,
+            BurlyWood => Color::BURLY_WOOD,
+            Tan => Color::TAN,
+            RosyBrown => Color::ROSY_BROWN,
+            SandyBrown => Color::SANDY_BROWN,
+            Goldenrod => Color::GOLDENROD,
+            DarkGoldenrod => Color::DARK_GOLDENROD,
+            Peru => Color::PERU,
+            Chocolate => Color::CHOCOLATE,
+            SaddleBrown => Color::SADDLE_BROWN,
+            Sienna => Color::SIENNA,
+            Brown => Color::BROWN,
+            Maroon => Color::MAROON,
+            White => Color::WHITE,
+            Snow => Color::SNOW,
+            HoneyDew => Color::HONEY_DEW,
+            MintCream => Color::MINT_CREAM,
+            Azure => Color::AZURE,
+            AliceBlue => Color::ALICE_BLUE,
+            GhostWhite => Color::GHOST_WHITE,
+            WhiteSmoke => Color::WHITE_SMOKE,
+            SeaShell => Color::SEA_SHELL,
+            Beige => Color::BEIGE,
+            OldLace => Color::OLD_LACE,
+            FloralWhite => Color::FLORAL_WHITE,
+            Ivory => Color::IVORY,
+            AntiqueWhite => Color::ANTIQUE_WHITE,
+            Linen => Color::LINEN,
+            LavenderBlush => Color::LAVENDER_BLUSH,
+            MistyRose => Color::MISTY_ROSE,
+            Gainsboro => Color::GAINSBORO,
+            LightGray => Color::LIGHT_GRAY,
+            Silver => Color::SILVER,
+            DarkGray => Color::DARK_GRAY,
+            Gray => Color::GRAY,
+            DimGray => Color::DIM_GRAY,
+            LightSlateGray => Color::LIGHT_SLATE_GRAY,
+            SlateGray => Color::SLATE_GRAY,
+            DarkSlateGray => Color::DARK_SLATE_GRAY,
+            Black => Color::BLACK,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum RawColor {
+    Rgba(u8, u8, u8, u8),
+    /// Hue in degrees, saturation and lightness between 0 and 1.
+    Hsla(f32, f32, f32, u8),
+    // Red HTML Color Names
+    /// rgb(205, 92, 92)
+    IndianRed,
+    /// rgb(240, 128, 128)
+    LightCoral,
+    /// rgb(250, 128, 114)
+    Salmon,
+    /// rgb(233, 150, 122)
+    DarkSalmon,
+    /// rgb(255, 160, 122)
+    LightSalmon,
+    /// rgb(220, 20, 60)
+    Crimson,
+    /// rgb(255, 0, 0)
+    Red,
+    /// rgb(178, 34, 34)
+    FireBrick,
+    /// rgb(139, 0, 0)
+    DarkRed,
+    // Pink HTML Color Names
+    /// rgb(255, 192, 203)
+    Pink,
+    /// rgb(255, 182, 193)
+    LightPink,
+    /// rgb(255, 105, 180)
+    HotPink,
+    /// rgb(255, 20, 147)
+    DeepPink,
+    /// rgb(199, 21, 133)
+    MediumVioletRed,
+    /// rgb(219, 112, 147)
+    PaleVioletRed,
+    //Orange HTML Color Names
+    // /// rgb(255, 160, 122) redefined
+    // LightSalmon,
+    /// rgb(255, 127, 80)
+    Coral,
+    /// rgb(255, 99, 71)
+    Tomato,
+    /// rgb(255, 69, 0)
+    OrangeRed,
+    /// rgb(255, 140, 0)
+    DarkOrange,
+    /// rgb(255, 165, 0)
+    Orange,
+    // Yellow HTML Color Names
+    /// rgb(255, 215, 0)
+    Gold,
+    /// rgb(255, 255, 0)
+    Yellow,
+    /// rgb(255, 255, 224)
+    LightYellow,
+    /// rgb(255, 250, 205)
+    LemonChiffon,
+    /// rgb(250, 250, 210)
+    LightGoldenrodYellow,
+    /// rgb(255, 239, 213)
+    PapayaWhip,
+    /// rgb(255, 228, 181)
+    Moccasin,
+    /// rgb(255, 218, 185)
+    PeachPuff,
+    /// rgb(238, 232, 170)
+    PaleGoldenrod,
+    /// rgb(240, 230, 140)
+    Khaki,
+    /// rgb(189, 183, 107)
+    DarkKhaki,
+    // Purple HTML Color Names
+    /// rgb(230, 230, 250)
+    Lavender,
+    /// rgb(216, 191, 216)
+    Thistle,
+    /// rgb(221, 160, 221)
+    Plum,
+    /// rgb(238, 130, 238)
+    Violet,
+    /// rgb(218, 112, 214)
+    Orchid,
+    /// rgb(255, 0, 255)
+    Fuchsia,
+    /// rgb(255, 0, 255)
+    Magenta,
+    /// rgb(186, 85, 211)
+    MediumOrchid,
+    /// rgb(147, 112, 219)
+    MediumPurple,
+    /// rgb(102, 51, 153)
+    RebeccaPurple,
+    /// rgb(138, 43, 226)
+    BlueViolet,
+    /// rgb(148, 0, 211)
+    DarkViolet,
+    /// rgb(153, 50, 204)
+    DarkOrchid,
+    /// rgb(139, 0, 139)
+    DarkMagenta,
+    /// rgb(128, 0, 128)
+    Purple,
+    /// rgb(75, 0, 130)
+    Indigo,
+    /// rgb(106, 90, 205)
+    SlateBlue,
+    /// rgb(72, 61, 139)
+    DarkSlateBlue,
+    /// rgb(123, 104, 238)
+    MediumSlateBlue,
+    // Green HTML Color Names
+    /// rgb(173, 255, 47)
+    GreenYellow,
+    /// rgb(127, 255, 0)
+    Chartreuse,
+    /// rgb(124, 252, 0)
+    LawnGreen,
+    /// rgb(0, 255, 0)
+    Lime,
+    /// rgb(50, 205, 50)
+    LimeGreen,
+    /// rgb(152, 251, 152)
+    PaleGreen,
+    /// rgb(144, 238, 144)
+    LightGreen,
+    /// rgb(0, 250, 154)
+    MediumSpringGreen,
+    /// rgb(0, 255, 127)
+    SpringGreen,
+    /// rgb(60, 179, 113)
+    MediumSeaGreen,
+    /// rgb(46, 139, 87)
+    SeaGreen,
+    /// rgb(34, 139, 34)
+    ForestGreen,
+    /// rgb(0, 128, 0)
+    Green,
+    /// rgb(0, 100, 0)
+    DarkGreen,
+    /// rgb(154, 205, 50)
+    YellowGreen,
+    /// rgb(107, 142, 35)
+    OliveDrab,
+    /// rgb(128, 128, 0)
+    Olive,
+    /// rgb(85, 107, 47)
+    DarkOliveGreen,
+    /// rgb(102, 205, 170)
+    MediumAquamarine,
+    /// rgb(143, 188, 139)
+    DarkSeaGreen,
+    /// rgb(32, 178, 170)
+    LightSeaGreen,
+    /// rgb(0, 139, 139)
+    DarkCyan,
+    /// rgb(0, 128, 128)
+    Teal,
+    // Blue HTML Color Names
+    /// rgb(0, 255, 255)
+    Aqua,
+    /// rgb(0, 255, 255)
+    Cyan,
+    /// rgb(224, 255, 255)
+    LightCyan,
+    /// rgb(175, 238, 238)
+    PaleTurquoise,
+    /// rgb(127, 255, 212)
+    Aquamarine,
+    /// rgb(64, 224, 208)
+    Turquoise,
+    /// rgb(72, 209, 204)
+    MediumTurquoise,
+    /// rgb(0, 206, 209)
+    DarkTurquoise,
+    /// rgb(95, 158, 160)
+    CadetBlue,
+    /// rgb(70, 130, 180)
+    SteelBlue,
+    /// rgb(176, 196, 222)
+    LightSteelBlue,
+    /// rgb(176, 224, 230)
+    PowderBlue,
+    /// rgb(173, 216, 230)
+    LightBlue,
+    /// rgb(135, 206, 235)
+    SkyBlue,
+    /// rgb(135, 206, 250)
+    LightSkyBlue,
+    /// rgb(0, 191, 255)
+    DeepSkyBlue,
+    /// rgb(30, 144, 255)
+    DodgerBlue,
+    /// rgb(100, 149, 237)
+    CornflowerBlue,
+    // /// rgb(123, 104, 238) duplicate
+    //MediumSlateBlue,
+    /// rgb(65, 105, 225)
+    RoyalBlue,
+    /// rgb(0, 0, 255)
+    Blue,
+    /// rgb(0, 0, 205)
+    MediumBlue,
+    /// rgb(0, 0, 139)
+    DarkBlue,
+    /// rgb(0, 0, 128)
+    Navy,
+    /// rgb(25, 25, 112)
+    MidnightBlue,
+    // Brown HTML Color Names
+    /// rgb(255, 248, 220)
+    Cornsilk,
+    /// rgb(255, 235, 205)
+    BlanchedAlmond,
+    /// rgb(255, 228, 196)
+    Bisque,
+    /// rgb(255, 222, 173)
+    NavajoWhite,
+    /// rgb(245, 222, 179)
+    Wheat,
+    /// rgb(222, 184, 135)
+    BurlyWood,
+    /// rgb(210, 180, 140)
+    Tan,
+    /// rgb(188, 143, 143)
+    RosyBrown,
+    /// rgb(244, 164, 96)
+    SandyBrown,
+    /// rgb(218, 165, 32)
+    Goldenrod,
+    /// rgb(184, 134, 11)
+    DarkGoldenrod,
+    /// rgb(205, 133, 63)
+    Peru,
+    /// rgb(210, 105, 30)
+    Chocolate,
+    /// rgb(139, 69, 19)
+    SaddleBrown,
+    /// rgb(160, 82, 45)
+    Sienna,
+    /// rgb(165, 42, 42)
+    Brown,
+    /// rgb(128, 0, 0)
+    Maroon,
+    // White HTML Color Names
+    /// rgb(255, 255, 255)
+    White,
+    /// rgb(255, 250, 250)
+    Snow,
+    /// rgb(240, 255, 240)
+    HoneyDew,
+    /// rgb(245, 255, 250)
+    MintCream,
+    /// rgb(240, 255, 255)
+    Azure,
+    /// rgb(240, 248, 255)
+    AliceBlue,
+    /// rgb(248, 248, 255)
+    GhostWhite,
+    /// rgb(245, 245, 245)
+    WhiteSmoke,
+    /// rgb(255, 245, 238)
+    SeaShell,
+    /// rgb(245, 245, 220)
+    Beige,
+    /// rgb(253, 245, 230)
+    OldLace,
+    /// rgb(255, 250, 240)
+    FloralWhite,
+    /// rgb(255, 255, 240)
+    Ivory,
+    /// rgb(250, 235, 215)
+    AntiqueWhite,
+    /// rgb(250, 240, 230)
+    Linen,
+    /// rgb(255, 240, 245)
+    LavenderBlush,
+    /// rgb(255, 228, 225)
+    MistyRose,
+    // Gray HTML Color Names
+    /// rgb(220, 220, 220)
+    Gainsboro,
+    /// rgb(211, 211, 211)
+    LightGray,
+    /// rgb(192, 192, 192)
+    Silver,
+    /// rgb(169, 169, 169)
+    DarkGray,
+    /// rgb(128, 128, 128)
+    Gray,
+    /// rgb(105, 105, 105)
+    DimGray,
+    /// rgb(119, 136, 153)
+    LightSlateGray,
+    /// rgb(112, 128, 144)
+    SlateGray,
+    /// rgb(47, 79, 79)
+    DarkSlateGray,
+    /// rgb(0, 0, 0)
+    Black,
+}
+
+impl RawColor {
+    fn parse(input: &str) -> Result<Self, ParseColorError> {
+        let text = input.trim();
+        let start = input.len() - input.trim_start().len();
+        let span = start..start + text.len();
+        if text.is_empty() {
+            return Err(ParseColorError::new("expected a color", 0..input.len()));
+        }
+        if text.starts_with('#') {
+            Self::parse_hex(text, start)
+        } else if let Some(open) = text.find('(') {
+            Self::parse_function(text, start, open)
+        } else {
+            Self::from_named(text)
+                .ok_or_else(|| ParseColorError::new(format!("unknown color `{}`", text), span))
+        }
+    }
+
+    #[inline]
+    fn from_named(name: &str) -> Option<Self> {
+        use RawColor::*;
+        let name = name.to_ascii_lowercase();
+        Some(match name.as_str() {
+            "indianred" => IndianRed,
+            "lightcoral" => LightCoral,
+            "salmon" => Salmon,
+            "darksalmon" => DarkSalmon,
+            "lightsalmon" => LightSalmon,
+            "crimson" => Crimson,
+            "red" => Red,
+            "firebrick" => FireBrick,
+            "darkred" => DarkRed,
+            "pink" => Pink,
+            "lightpink" => LightPink,
+            "hotpink" => HotPink,
+            "deeppink" => DeepPink,
+            "mediumvioletred" => MediumVioletRed,
+            "palevioletred" => PaleVioletRed,
+            "coral" => Coral,
+            "tomato" => Tomato,
+            "orangered" => OrangeRed,
+            "darkorange" => DarkOrange,
+            "orange" => Orange,
+            "gold" => Gold,
+            "yellow" => Yellow,
+            "lightyellow" => LightYellow,
+            "lemonchiffon" => LemonChiffon,
+            "lightgoldenrodyellow" => LightGoldenrodYellow,
+            "papayawhip" => PapayaWhip,
+            "moccasin" => Moccasin,
+            "peachpuff" => PeachPuff,
+            "palegoldenrod" => PaleGoldenrod,
+            "khaki" => Khaki,
+            "darkkhaki" => DarkKhaki,
+            "lavender" => Lavender,
+            "thistle" => Thistle,
+            "plum" => Plum,
+            "violet" => Violet,
+            "orchid" => Orchid,
+            "fuchsia" => Fuchsia,
+            "magenta" => Magenta,
+            "mediumorchid" => MediumOrchid,
+            "mediumpurple" => MediumPurple,
+            "rebeccapurple" => RebeccaPurple,
+            "blueviolet" => BlueViolet,
+            "darkviolet" => DarkViolet,
+            "darkorchid" => DarkOrchid,
+            "darkmagenta" => DarkMagenta,
+            "purple" => Purple,
+            "indigo" => Indigo,
+            "slateblue" => SlateBlue,
+            "darkslateblue" => DarkSlateBlue,
+            "mediumslateblue" => MediumSlateBlue,
+            "greenyellow" => GreenYellow,
+            "chartreuse" => Chartreuse,
+            "lawngreen" => LawnGreen,
+            "lime" => Lime,
+            "limegreen" => LimeGreen,
+            "palegreen" => PaleGreen,
+            "lightgreen" => LightGreen,
+            "mediumspringgreen" => MediumSpringGreen,
+            "springgreen" => SpringGreen,
+            "mediumseagreen" => MediumSeaGreen,
+            "seagreen" => SeaGreen,
+            "forestgreen" => ForestGreen,
+            "green" => Green,
+            "darkgreen" => DarkGreen,
+            "yellowgreen" => YellowGreen,
+            "olivedrab" => OliveDrab,
+            "olive" => Olive,
+            "darkolivegreen" => DarkOliveGreen,
+            "mediumaquamarine" => MediumAquamarine,
+            "darkseagreen" => DarkSeaGreen,
+            "lightseagreen" => LightSeaGreen,
+            "darkcyan" => DarkCyan,
+            "teal" => Teal,
+            "aqua" => Aqua,
+            "cyan" => Cyan,
+            "lightcyan" => LightCyan,
+            "paleturquoise" => PaleTurquoise,
+            "aquamarine" => Aquamarine,
+            "turquoise" => Turquoise,
+            "mediumturquoise" => MediumTurquoise,
+            "darkturquoise" => DarkTurquoise,
+            "cadetblue" => CadetBlue,
+            "steelblue" => SteelBlue,
+            "lightsteelblue" => LightSteelBlue,
+            "powderblue" => PowderBlue,
+            "lightblue" => LightBlue,
+            "skyblue" => SkyBlue,
+            "lightskyblue" => LightSkyBlue,
+            "deepskyblue" => DeepSkyBlue,
+            "dodgerblue" => DodgerBlue,
+            "cornflowerblue" => CornflowerBlue,
+            "royalblue" => RoyalBlue,
+            "blue" => Blue,
+            "mediumblue" => MediumBlue,
+            "darkblue" => DarkBlue,
+            "navy" => Navy,
+            "midnightblue" => MidnightBlue,
+            "cornsilk" => Cornsilk,
+            "blanchedalmond" => BlanchedAlmond,
+            "bisque" => Bisque,
+            "navajowhite" => NavajoWhite,
+            "wheat" => Wheat,
+            "burlywood" => BurlyWood,
+            "tan" => Tan,
+            "rosybrown" => RosyBrown,
+            "sandybrown" => SandyBrown,
+            "goldenrod" => Goldenrod,
+            "darkgoldenrod" => DarkGoldenrod,
+            "peru" => Peru,
+            "chocolate" => Chocolate,
+            "saddlebrown" => SaddleBrown,
+            "sienna" => Sienna,
+            "brown" => Brown,
+            "maroon" => Maroon,
+            "white" => White,
+            "snow" => Snow,
+            "honeydew" => HoneyDew,
+            "mintcream" => MintCream,
+            "azure" => Azure,
+            "aliceblue" => AliceBlue,
+            "ghostwhite" => GhostWhite,
+            "whitesmoke" => WhiteSmoke,
+            "seashell" => SeaShell,
+            "beige" => Beige,
+            "oldlace" => OldLace,
+            "floralwhite" => FloralWhite,
+            "ivory" => Ivory,
+            "antiquewhite" => AntiqueWhite,
+            "linen" => Linen,
+            "lavenderblush" => LavenderBlush,
+            "mistyrose" => MistyRose,
+            "gainsboro" => Gainsboro,
+            "lightgray" | "lightgrey" => LightGray,
+            "silver" => Silver,
+            "darkgray" | "darkgrey" => DarkGray,
+            "gray" | "grey" => Gray,
+            "dimgray" | "dimgrey" => DimGray,
+            "lightslategray" | "lightslategrey" => LightSlateGray,
+            "slategray" | "slategrey" => SlateGray,
+            "darkslategray" | "darkslategrey" => DarkSlateGray,
+            "black" => Black,
+            _ => return None,
+        })
+    }
+
+    /// Parse `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`. `offset` is where `text` starts in the
+    /// input, for errors.
+    fn parse_hex(text: &str, offset: usize) -> Result<Self, ParseColorError> {
+        let result: IResult<&str, &str> =
+            nom::bytes::complete::take_while(|c: char| c.is_ascii_hexdigit())(&text[1..]);
+        let (rest, digits) = result.expect("take_while can't fail");
+        if let Some(bad) = rest.chars().next() {
+            let pos = offset + text.len() - rest.len();
+            return Err(ParseColorError::new(
+                format!("`{}` is not a hex digit", bad),
+                pos..pos + bad.len_utf8(),
+            ));
+        }
+        let digit = |i: usize| u8::from_str_radix(&digits[i..i + 1], 16).unwrap();
+        let pair = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).unwrap();
+        Ok(match digits.len() {
+            3 => RawColor::Rgba(digit(0) * 17, digit(1) * 17, digit(2) * 17, u8::MAX),
+            4 => RawColor::Rgba(digit(0) * 17, digit(1) * 17, digit(2) * 17, digit(3) * 17),
+            6 => RawColor::Rgba(pair(0), pair(2), pair(4), u8::MAX),
+            8 => RawColor::Rgba(pair(0), pair(2), pair(4), pair(6)),
+            n => {
+                return Err(ParseColorError::new(
+                    format!("expected 3, 4, 6 or 8 hex digits, found {}", n),
+                    offset..offset + text.len(),
+                ))
+            }
+        })
+    }
+
+    /// Parse `rgb(..)`, `rgba(..)`, `hsl(..)` or `hsla(..)`. `open` is the index of the `(`.
+    fn parse_function(text: &str, offset: usize, open: usize) -> Result<Self, ParseColorError> {
+        let name = text[..open].trim_end();
+        let arity = match name.to_ascii_lowercase().as_str() {
+            "rgb" | "hsl" => 3,
+            "rgba" | "hsla" => 4,
+            _ => {
+                return Err(ParseColorError::new(
+                    format!("unknown color function `{}`", name),
+                    offset..offset + name.len(),
+                ))
+            }
+        };
+        if !text.ends_with(')') {
+            return Err(ParseColorError::new(
+                "expected `)` at the end",
+                offset + open..offset + text.len(),
+            ));
+        }
+        let args = Arg::split(&text[open + 1..text.len() - 1], offset + open + 1);
+        if args.len() != arity {
+            return Err(ParseColorError::new(
+                format!("`{}` takes {} arguments, found {}", name, arity, args.len()),
+                offset..offset + text.len(),
+            ));
+        }
+        let alpha = match args.get(3) {
+            Some(arg) => arg.alpha()?,
+            None => u8::MAX,
+        };
+        Ok(if name.starts_with(['r', 'R']) {
+            RawColor::Rgba(
+                args[0].channel()?,
+                args[1].channel()?,
+                args[2].channel()?,
+                alpha,
+            )
+        } else {
+            RawColor::Hsla(
+                args[0].hue()?,
+                args[1].percentage()?,
+                args[2].percentage()?,
+                alpha,
+            )
+        })
+    }
+}
+
+/// An argument to a color function, with where it is in the input.
+struct Arg<'a> {
+    text: &'a str,
+    span: Range<usize>,
+}
+
+impl<'a> Arg<'a> {
+    /// Split the inside of a color function at commas. `offset` is where `args` starts in the
+    /// input.
+    fn split(args: &'a str, offset: usize) -> Vec<Self> {
+        let mut start = 0;
+        args.split(',')
+            .map(|raw| {
+                let text = raw.trim();
+                let lead = raw.len() - raw.trim_start().len();
+                let pos = offset + start + lead;
+                start += raw.len() + 1;
+                // point at the comma or `)` after a missing argument
+                let end = if text.is_empty() {
+                    pos + 1
+                } else {
+                    pos + text.len()
+                };
+                Arg {
+                    text,
+                    span: pos..end,
+                }
+            })
+            .collect()
+    }
+
+    fn error(&self, msg: impl Into<String>) -> ParseColorError {
+        ParseColorError::new(msg, self.span.clone())
+    }
+
+    /// The argument as a number followed by a unit (which may be empty).
+    fn number(&self) -> Result<(f32, &'a str), ParseColorError> {
+        let result: IResult<&str, &str> = nom::number::complete::recognize_float(self.text);
+        // a unit starting with `e` looks like an exponent, so fall back to just the digits
+        let result: IResult<&str, &str> =
+            result.or_else(|_| nom::character::complete::digit1(self.text));
+        let (unit, number) = result.map_err(|_| {
+            if self.text.is_empty() {
+                self.error("missing argument")
+            } else {
+                self.error(format!("expected a number, found `{}`", self.text))
+            }
+        })?;
+        let value = number
+            .parse()
+            .map_err(|_| self.error(format!("expected a number, found `{}`", number)))?;
+        Ok((value, unit))
+    }
+
+    /// Check `value` is between 0 and `max`.
+    fn in_range(&self, value: f32, max: f32) -> Result<f32, ParseColorError> {
+        if value >= 0.0 && value <= max {
+            Ok(value)
+        } else {
+            Err(self.error(format!("`{}` is not between 0 and {}", self.text, max)))
+        }
+    }
+
+    /// A red, green or blue channel, from 0 to 255 or as a percentage.
+    fn channel(&self) -> Result<u8, ParseColorError> {
+        let value = match self.number()? {
+            (value, "") => self.in_range(value, 255.0)?,
+            (value, "%") => self.in_range(value, 100.0)? * 2.55,
+            (_, unit) => return Err(self.error(format!("unexpected unit `{}`", unit))),
+        };
+        Ok(value.round() as u8)
+    }
+
+    /// A hue in degrees, wrapped to between 0 and 360.
+    fn hue(&self) -> Result<f32, ParseColorError> {
+        match self.number()? {
+            (value, "") | (value, "deg") => Ok(value.rem_euclid(360.0)),
+            (_, unit) => Err(self.error(format!("expected a hue in `deg`, found `{}`", unit))),
+        }
+    }
+
+    /// A saturation or lightness percentage, between 0 and 1.
+    fn percentage(&self) -> Result<f32, ParseColorError> {
+        match self.number()? {
+            (value, "") | (value, "%") => Ok(self.in_range(value, 100.0)? / 100.0),
+            (_, unit) => Err(self.error(format!("expected a percentage, found `{}`", unit))),
+        }
+    }
+
+    /// An alpha from 0 to 1 or as a percentage.
+    fn alpha(&self) -> Result<u8, ParseColorError> {
+        let value = match self.number()? {
+            (value, "") => self.in_range(value, 1.0)?,
+            (value, "%") => self.in_range(value, 100.0)? / 100.0,
+            (_, unit) => return Err(self.error(format!("unexpected unit `{}`", unit))),
+        };
+        Ok((value * 255.0).round() as u8)
+    }
+}
+
+/// Convert a hue in degrees, and saturation and lightness between 0 and 1, to rgb.
+fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> Color {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let h = (hue % 360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let to_u8 = |v: f32| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Color::rgb(to_u8(r), to_u8(g), to_u8(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Color {
+        match Color::parse(input) {
+            Ok(color) => color,
+            Err(e) => panic!("could not parse {:?}: {}", input, e),
+        }
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(parse("steelblue"), Color::STEEL_BLUE);
+        assert_eq!(parse("SteelBlue"), Color::STEEL_BLUE);
+        assert_eq!(parse("  black "), Color::BLACK);
+        assert_eq!("red".parse::<Color>(), Ok(Color::RED));
+    }
+
+    #[test]
+    fn grey_spellings() {
+        let tests = [
+            ("grey", Color::GRAY),
+            ("darkgrey", Color::DARK_GRAY),
+            ("dimgrey", Color::DIM_GRAY),
+            ("lightgrey", Color::LIGHT_GRAY),
+            ("slategrey", Color::SLATE_GRAY),
+            ("darkslategrey", Color::DARK_SLATE_GRAY),
+            ("lightslategrey", Color::LIGHT_SLATE_GRAY),
+        ];
+        for &(input, expected) in tests.iter() {
+            assert_eq!(parse(input), expected, "{}", input);
+            assert_eq!(parse(&input.replace("grey", "gray")), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn hex() {
+        let tests = [
+            ("#f80", Color::rgb(255, 136, 0)),
+            ("#f808", Color::rgba(255, 136, 0, 136)),
+            ("#ff8800", Color::rgb(255, 136, 0)),
+            ("#FF8800", Color::rgb(255, 136, 0)),
+            ("#ff880080", Color::rgba(255, 136, 0, 128)),
+            ("#00000000", Color::TRANSPARENT),
+        ];
+        for &(input, expected) in tests.iter() {
+            assert_eq!(parse(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn rgb() {
+        let tests = [
+            ("rgb(255, 136, 0)", Color::rgb(255, 136, 0)),
+            ("RGB(255,136,0)", Color::rgb(255, 136, 0)),
+            ("rgb( 1.4 , 2.6 , 3 )", Color::rgb(1, 3, 3)),
+            ("rgb(100%, 50%, 0%)", Color::rgb(255, 128, 0)),
+            ("rgba(255, 136, 0, 0.5)", Color::rgba(255, 136, 0, 128)),
+            ("rgba(255, 136, 0, 1)", Color::rgb(255, 136, 0)),
+            ("rgba(0, 0, 0, 25%)", Color::rgba(0, 0, 0, 64)),
+            ("rgba(0, 0, 0, .5)", Color::rgba(0, 0, 0, 128)),
+        ];
+        for &(input, expected) in tests.iter() {
+            assert_eq!(parse(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn hsl() {
+        let tests = [
+            ("hsl(0, 100%, 50%)", Color::rgb(255, 0, 0)),
+            ("hsl(120, 100%, 50%)", Color::rgb(0, 255, 0)),
+            ("hsl(120deg, 100, 50)", Color::rgb(0, 255, 0)),
+            ("hsl(480deg, 100%, 50%)", Color::rgb(0, 255, 0)),
+            ("hsl(-240deg, 100%, 50%)", Color::rgb(0, 255, 0)),
+            ("hsl(240, 100%, 25%)", Color::rgb(0, 0, 128)),
+            ("hsl(0, 0%, 100%)", Color::WHITE),
+            ("HSLA(0, 100%, 50%, 0.25)", Color::rgba(255, 0, 0, 64)),
+            ("hsla(0, 100%, 50%, 50%)", Color::rgba(255, 0, 0, 128)),
+        ];
+        for &(input, expected) in tests.iter() {
+            assert_eq!(parse(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let tests = [
+            ("", "expected a color", 0..0),
+            ("  ", "expected a color", 0..2),
+            ("nope", "unknown color `nope`", 0..4),
+            (" nope ", "unknown color `nope`", 1..5),
+            ("#12g4", "`g` is not a hex digit", 3..4),
+            ("#12é", "`é` is not a hex digit", 3..5),
+            ("#12345", "expected 3, 4, 6 or 8 hex digits, found 5", 0..6),
+            (" #12345", "expected 3, 4, 6 or 8 hex digits, found 5", 1..7),
+            ("cmyk(1, 2, 3)", "unknown color function `cmyk`", 0..4),
+            ("rgb(1, 2, 3", "expected `)` at the end", 3..11),
+            ("rgb(1, 2)", "`rgb` takes 3 arguments, found 2", 0..9),
+            ("hsla(1, 2, 3)", "`hsla` takes 4 arguments, found 3", 0..13),
+            ("rgb(1, , 3)", "missing argument", 7..8),
+            ("rgb(1, 2, )", "missing argument", 10..11),
+            ("rgb(a, 0, 0)", "expected a number, found `a`", 4..5),
+            ("rgb(1, 256, 3)", "`256` is not between 0 and 255", 7..10),
+            ("rgb(1, -1, 3)", "`-1` is not between 0 and 255", 7..9),
+            ("rgb(1, 2px, 3)", "unexpected unit `px`", 7..10),
+            ("rgb(101%, 0, 0)", "`101%` is not between 0 and 100", 4..8),
+            ("rgba(0, 0, 0, 2)", "`2` is not between 0 and 1", 14..15),
+            (
+                "hsl(30rad, 50%, 50%)",
+                "expected a hue in `deg`, found `rad`",
+                4..9,
+            ),
+            (
+                "hsl(0, 150%, 50%)",
+                "`150%` is not between 0 and 100",
+                7..11,
+            ),
+            (
+                "hsl(0, 50%, 5em)",
+                "expected a percentage, found `em`",
+                12..15,
+            ),
+        ];
+        for (input, msg, span) in tests.iter().cloned() {
+            match Color::parse(input) {
+                Ok(color) => panic!("{:?} parsed as {:?}", input, color),
+                Err(e) => {
+                    assert_eq!(e.to_string(), msg, "{:?}", input);
+                    assert_eq!(e.span(), span, "{:?}", input);
+                }
+            }
+        }
+    }
+}
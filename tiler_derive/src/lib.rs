@@ -1,6 +1,5 @@
 extern crate proc_macro;
 
-use crate::glyph::Color;
 use proc_macro::TokenStream;
 use proc_macro2::{Literal, TokenStream as TokStr2};
 use quote::quote;
@@ -12,8 +11,7 @@ use syn::{
     spanned::Spanned,
     token, Data, DataEnum, DeriveInput, Error, Ident, LitChar, LitStr, Result, Token, Variant,
 };
-
-mod glyph;
+use tiler_color::{Color, ParseColorError};
 
 mod kw {
     syn::custom_keyword!(char);
@@ -138,8 +136,8 @@ fn real_derive_tileset(input: DeriveInput) -> Result<TokStr2> {
                  fg_color,
                  bg_color,
              }| {
-                let fg = fg_color.as_array();
-                let bg = bg_color.as_array();
+                let fg = color_tokens(*fg_color);
+                let bg = color_tokens(*bg_color);
                 quote! {
                     #enum_ident :: #ident => tiler::Char {
                         ch: #character,
@@ -167,6 +165,51 @@ fn real_derive_tileset(input: DeriveInput) -> Result<TokStr2> {
     })
 }
 
+/// The tokens for a color as a `tiler::Color` constant.
+fn color_tokens(color: Color) -> TokStr2 {
+    let Color { r, g, b, a } = color;
+    quote! {
+        tiler::Color::rgba(#r, #g, #b, #a)
+    }
+}
+
+/// Parse the color in an attribute, pointing any error at the bad part of the string where the
+/// compiler supports it.
+fn parse_color(lit_str: &LitStr) -> Result<Color> {
+    let value = lit_str.value();
+    Color::parse(&value).map_err(|e| {
+        let token: Literal = lit_str.token();
+        let repr = token.to_string();
+        // offsets into the value only line up with the source if there are no escapes
+        let span = if repr == format!("\"{}\"", value) {
+            let range = e.span();
+            token.subspan(range.start + 1..range.end + 1)
+        } else {
+            None
+        };
+        match span {
+            Some(span) => Error::new(span, e),
+            // `subspan` only works on nightly, so say where the problem is instead
+            None => Error::new(lit_str.span(), locate_error(&value, &e)),
+        }
+    })
+}
+
+/// The error message, followed by the part of `value` it is about and where that starts, unless
+/// it is about the whole thing.
+fn locate_error(value: &str, e: &ParseColorError) -> String {
+    let range = e.span();
+    if range.is_empty() || range == (0..value.len()) {
+        return e.to_string();
+    }
+    format!(
+        "{} (`{}` at byte {})",
+        e,
+        &value[range.clone()],
+        range.start
+    )
+}
+
 /// Go through all the variants of the enum and work out the associated macro information (like
 /// which one is the default, etc.)
 fn get_all_tile_info(data: &DataEnum, input: &DeriveInput) -> Result<TileSetInfo> {
@@ -204,18 +247,12 @@ fn get_all_tile_info(data: &DataEnum, input: &DeriveInput) -> Result<TileSetInfo
                     keyword: _,
                     equals: _,
                     lit_str,
-                } => match Color::parse(&lit_str.value()) {
-                    Ok(color) => fg_color = color,
-                    Err(msg) => return Err(Error::new(lit_str.span(), msg)),
-                },
+                } => fg_color = parse_color(&lit_str)?,
                 TileAttr::BgColor {
                     keyword: _,
                     equals: _,
                     lit_str,
-                } => match Color::parse(&lit_str.value()) {
-                    Ok(color) => bg_color = color,
-                    Err(msg) => return Err(Error::new(lit_str.span(), msg)),
-                },
+                } => bg_color = parse_color(&lit_str)?,
             };
         }
         let character = match character {
@@ -255,3 +292,20 @@ fn get_tile_attrs(variant: &Variant) -> Result<Vec<TileAttr>> {
     }
     Ok(attrs)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_say_where_they_are() {
+        let message = |value: &str| locate_error(value, &Color::parse(value).unwrap_err());
+        assert_eq!(message("#12g4"), "`g` is not a hex digit (`g` at byte 3)");
+        assert_eq!(
+            message("rgb(1, 300, 3)"),
+            "`300` is not between 0 and 255 (`300` at byte 7)"
+        );
+        assert_eq!(message("nope"), "unknown color `nope`");
+        assert_eq!(message(""), "expected a color");
+    }
+}